@@ -1,6 +1,9 @@
 use std::fs::read_to_string;
 
 use divan::Bencher;
+use jsonic::json_item::JsonItem;
+use jsonic::json_type::JsonType;
+use jsonic::parse_options::{NumberDecoding, ParseOptions};
 
 fn main() {
     divan::main();
@@ -10,12 +13,46 @@ fn parse_file(bencher: Bencher, path: &str) {
     let in_memory_json = read_to_string(path).unwrap();
     assert!(jsonic::parse(&in_memory_json).is_ok());
 
-    bencher.bench_local(move || {
+    bencher.bench_local(|| {
         jsonic::parse(&in_memory_json)
     });
 }
 
+// Decodes every number in the tree, as integers when possible
+fn read_numbers(item: &JsonItem) -> f64 {
+    match item.get_type() {
+        JsonType::JsonNumber => {
+            match item.as_i64() {
+                Some(integer) => { integer as f64 }
+                None => { item.as_f64().unwrap() }
+            }
+        }
+        JsonType::JsonArray => { item.elements().unwrap().map(read_numbers).sum() }
+        JsonType::JsonMap => { item.entries().unwrap().map(|(_, item)| read_numbers(item)).sum() }
+        _ => { 0.0 }
+    }
+}
+
 #[divan::bench(args = ["./benches/data/canada.json", "./benches/data/citm_catalog.json", "./benches/data/twitter.json"])]
 fn parse(bencher: Bencher, path: &str) {
     parse_file(bencher, path);
-}
\ No newline at end of file
+}
+
+#[divan::bench(args = ["./benches/data/canada.json", "./benches/data/citm_catalog.json", "./benches/data/twitter.json"])]
+fn parse_and_read_numbers(bencher: Bencher, path: &str) {
+    let in_memory_json = read_to_string(path).unwrap();
+
+    bencher.bench_local(|| {
+        read_numbers(&jsonic::parse(&in_memory_json).unwrap())
+    });
+}
+
+#[divan::bench(args = ["./benches/data/canada.json", "./benches/data/citm_catalog.json", "./benches/data/twitter.json"])]
+fn parse_eager_and_read_numbers(bencher: Bencher, path: &str) {
+    let in_memory_json = read_to_string(path).unwrap();
+    let options = ParseOptions::new().number_decoding(NumberDecoding::Eager);
+
+    bencher.bench_local(|| {
+        read_numbers(&jsonic::parse_with(&in_memory_json, &options).unwrap())
+    });
+}
@@ -0,0 +1,133 @@
+use std::borrow::Cow;
+use std::str::from_utf8;
+
+use crate::json_error::JsonError;
+use crate::json_error::JsonErrorKind::InvalidUtf8;
+
+/// Unicode encodings a JSON text can be found in (RFC 8259, section 8.1)
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Encoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    Utf32Le,
+    Utf32Be,
+}
+
+impl Encoding {
+    fn bom_len(&self, bytes: &[u8]) -> usize {
+        let bom: &[u8] = match self {
+            Encoding::Utf8 => { &[0xef, 0xbb, 0xbf] }
+            Encoding::Utf16Le => { &[0xff, 0xfe] }
+            Encoding::Utf16Be => { &[0xfe, 0xff] }
+            Encoding::Utf32Le => { &[0xff, 0xfe, 0x00, 0x00] }
+            Encoding::Utf32Be => { &[0x00, 0x00, 0xfe, 0xff] }
+        };
+        if bytes.starts_with(bom) { bom.len() } else { 0 }
+    }
+}
+
+/// Detects the encoding of JSON data, from its byte order mark if present.
+/// Otherwise, the pattern of zero bytes in the first characters is used, as a JSON text always starts with ASCII characters.
+pub fn detect_encoding(bytes: &[u8]) -> Encoding {
+    match bytes {
+        [0xef, 0xbb, 0xbf, ..] => { Encoding::Utf8 }
+        [0xff, 0xfe, 0x00, 0x00, ..] | [_, 0x00, 0x00, 0x00, ..] => { Encoding::Utf32Le }
+        [0x00, 0x00, 0xfe, 0xff, ..] | [0x00, 0x00, 0x00, _, ..] => { Encoding::Utf32Be }
+        [0xff, 0xfe, ..] | [_, 0x00, ..] => { Encoding::Utf16Le }
+        [0xfe, 0xff, ..] | [0x00, _, ..] => { Encoding::Utf16Be }
+        _ => { Encoding::Utf8 }
+    }
+}
+
+/// Converts JSON data in any of the supported [`Encoding`]s to UTF-8 text.
+/// UTF-8 data is borrowed as is, other encodings are transcoded. A leading byte order mark is removed.
+///
+/// ```rust
+/// let utf16: Vec<u8> = "[\"é\"]".encode_utf16().flat_map(u16::to_le_bytes).collect();
+///
+/// let text = jsonic::encoding::transcode(&utf16).unwrap();
+/// assert_eq!(jsonic::parse(&text).unwrap()[0].as_str(), Some("é"));
+/// ```
+pub fn transcode(bytes: &[u8]) -> Result<Cow<'_, str>, JsonError> {
+    let encoding = detect_encoding(bytes);
+    let start = encoding.bom_len(bytes);
+    match encoding {
+        Encoding::Utf8 => {
+            match from_utf8(&bytes[start..]) {
+                Ok(text) => { Ok(Cow::Borrowed(text)) }
+                Err(error) => { Err(JsonError::with_kind(bytes, start + error.valid_up_to(), InvalidUtf8)) }
+            }
+        }
+        Encoding::Utf16Le | Encoding::Utf16Be => {
+            let mut text = String::with_capacity(bytes.len() - start);
+            let units = bytes[start..].chunks(2).map(|c| {
+                match (encoding, c) {
+                    (Encoding::Utf16Le, [a, b]) => { u16::from_le_bytes([*a, *b]) }
+                    (_, [a, b]) => { u16::from_be_bytes([*a, *b]) }
+                    _ => { 0xdc00 }     // Truncated unit, reported as an unpaired surrogate
+                }
+            });
+            let mut index = start;
+            for c in char::decode_utf16(units) {
+                match c {
+                    Ok(c) => {
+                        text.push(c);
+                        index += c.len_utf16() << 1;
+                    }
+                    Err(_) => { return Err(JsonError::with_kind(bytes, index, InvalidUtf8)); }
+                }
+            }
+            Ok(Cow::Owned(text))
+        }
+        Encoding::Utf32Le | Encoding::Utf32Be => {
+            let mut text = String::with_capacity(bytes.len() - start);
+            for (i, c) in bytes[start..].chunks(4).enumerate() {
+                let value = match (encoding, c) {
+                    (Encoding::Utf32Le, [a, b, c, d]) => { Some(u32::from_le_bytes([*a, *b, *c, *d])) }
+                    (_, [a, b, c, d]) => { Some(u32::from_be_bytes([*a, *b, *c, *d])) }
+                    _ => { None }
+                };
+                match value.and_then(char::from_u32) {
+                    Some(c) => { text.push(c); }
+                    None => { return Err(JsonError::with_kind(bytes, start + (i << 2), InvalidUtf8)); }
+                }
+            }
+            Ok(Cow::Owned(text))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::encoding::{detect_encoding, transcode, Encoding};
+
+    fn utf16(text: &str, big_endian: bool) -> Vec<u8> {
+        text.encode_utf16().flat_map(|u| if big_endian { u.to_be_bytes() } else { u.to_le_bytes() }).collect()
+    }
+
+    #[test]
+    fn detect() {
+        assert_eq!(detect_encoding(b"{}"), Encoding::Utf8);
+        assert_eq!(detect_encoding(b"\xef\xbb\xbf{}"), Encoding::Utf8);
+        assert_eq!(detect_encoding(&utf16("{}", false)), Encoding::Utf16Le);
+        assert_eq!(detect_encoding(&utf16("\u{feff}{}", true)), Encoding::Utf16Be);
+        assert_eq!(detect_encoding(b"[\0\0\0]\0\0\0"), Encoding::Utf32Le);
+        assert_eq!(detect_encoding(b"\0\0\0[\0\0\0]"), Encoding::Utf32Be);
+    }
+
+    #[test]
+    fn transcode_utf16() {
+        assert_eq!(transcode(&utf16("\u{feff}[\"😀\"]", false)).unwrap(), "[\"😀\"]");
+        assert_eq!(transcode(&utf16("[\"😀\"]", true)).unwrap(), "[\"😀\"]");
+        let mut broken = utf16("[\"😀\"]", true);
+        broken.drain(6..8);
+        assert_eq!(transcode(&broken).unwrap_err().get_index(), 4);
+    }
+
+    #[test]
+    fn transcode_utf32() {
+        let bytes: Vec<u8> = "[1,\"ü\"]".chars().flat_map(|c| (c as u32).to_le_bytes()).collect();
+        assert_eq!(transcode(&bytes).unwrap(), "[1,\"ü\"]");
+    }
+}
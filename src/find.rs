@@ -0,0 +1,137 @@
+use std::iter::FusedIterator;
+
+use crate::json_item::JsonItem;
+use crate::json_type::JsonType;
+use crate::jsonpath::NormalizedPath;
+use crate::walk::Walker;
+
+/// Iterator over the items matching a predicate, in document order, with their paths. See [`JsonItem::find_all`].
+/// Items are tested lazily, so stopping early saves walking the rest of the tree. Keys along the way are borrowed, paths
+/// being only built, and their keys decoded, for matches.
+pub struct Finder<'i, 'a, P> {
+    walker: Walker<'i, 'a>,
+    predicate: P,
+}
+
+impl<'i, 'a, P: FnMut(&JsonItem<'a>) -> bool> Finder<'i, 'a, P> {
+    pub(crate) fn new(item: &'i JsonItem<'a>, predicate: P) -> Self {
+        Finder { walker: Walker::new(item), predicate }
+    }
+}
+
+impl<'i, 'a, P: FnMut(&JsonItem<'a>) -> bool> Iterator for Finder<'i, 'a, P> {
+    type Item = (NormalizedPath, &'i JsonItem<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (item, depth) = self.walker.next_item()?;
+            if (self.predicate)(item) {
+                return Some((self.walker.path(depth), item));
+            }
+        }
+    }
+}
+
+impl<'a, P: FnMut(&JsonItem<'a>) -> bool> FusedIterator for Finder<'_, 'a, P> {}
+
+/// Matches objects containing key
+///
+/// ```rust
+/// use jsonic::find::has_key;
+///
+/// let parsed = jsonic::parse("[{\"id\": 1}, {\"name\": \"a\"}, \"id\"]").unwrap();
+///
+/// let paths: Vec<String> = parsed.find_all(has_key("id")).map(|(path, _)| path.to_string()).collect();
+/// assert_eq!(paths, ["$[0]"]);
+/// ```
+pub fn has_key(key: &str) -> impl Fn(&JsonItem<'_>) -> bool + '_ {
+    move |item| item.contains_key(key)
+}
+
+/// Matches objects containing key, with a string value equal to value once decoded
+///
+/// ```rust
+/// use jsonic::find::key_equals;
+///
+/// let parsed = jsonic::parse("{\"logs\": [{\"type\": \"info\"}, {\"type\": \"error\", \"code\": 7}]}").unwrap();
+///
+/// let (path, item) = parsed.find_first(key_equals("type", "error")).unwrap();
+/// assert_eq!((path.to_string().as_str(), item["code"].as_i128()), ("$['logs'][1]", Some(7)));
+/// ```
+pub fn key_equals<'k>(key: &'k str, value: &'k str) -> impl Fn(&JsonItem<'_>) -> bool + 'k {
+    move |item| item.get(key).and_then(JsonItem::as_string).is_some_and(|string| string == value)
+}
+
+/// Matches items of type `json_type`
+///
+/// ```rust
+/// use jsonic::find::type_is;
+/// use jsonic::json_type::JsonType::JsonNumber;
+///
+/// let parsed = jsonic::parse("{\"a\": [1, \"2\", {\"b\": 3}]}").unwrap();
+///
+/// assert_eq!(parsed.find_all(type_is(JsonNumber)).filter_map(|(_, item)| item.as_i128()).sum::<i128>(), 4);
+/// ```
+pub fn type_is(json_type: JsonType) -> impl Fn(&JsonItem<'_>) -> bool {
+    move |item| item.get_type() == &json_type
+}
+
+/// Matches strings containing pattern once decoded
+///
+/// ```rust
+/// use jsonic::find::string_contains;
+///
+/// let parsed = jsonic::parse("{\"a\": \"n\\u00e9e\", \"b\": [\"ne\", \"é\"], \"né\": 1}").unwrap();
+///
+/// assert_eq!(parsed.find_all(string_contains("é")).count(), 2);
+/// ```
+pub fn string_contains(pattern: &str) -> impl Fn(&JsonItem<'_>) -> bool + '_ {
+    move |item| item.as_string().is_some_and(|string| string.contains(pattern))
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants)]
+mod tests {
+    use crate::find::{has_key, key_equals, string_contains, type_is};
+    use crate::json_type::JsonType::{JsonMap, JsonString};
+    use crate::parse;
+
+    const JSON: &str = "{\"type\": \"error\", \"items\": [{\"type\": \"error\"}, {\"type\": \"warning\", \"detail\": {\"type\": \"error\"}}]}";
+
+    #[test]
+    fn find_all() {
+        match parse(JSON) {
+            Ok(parsed) => {
+                let paths: Vec<String> = parsed.find_all(key_equals("type", "error")).map(|(path, _)| path.to_dotted()).collect();
+                assert_eq!(paths, ["$", "$.items[0]", "$.items[1].detail"]);
+                assert_eq!(parsed.find_all(type_is(JsonMap)).count(), 4);
+                assert_eq!(parsed.find_all(has_key("detail")).count(), 1);
+                assert_eq!(parsed.find_all(string_contains("rr")).count(), 3);
+                assert_eq!(parsed.find_all(|item| type_is(JsonString)(item) && !string_contains("rr")(item)).count(), 1);
+                assert!(parsed.find_first(has_key("missing")).is_none());
+                assert!(parsed["missing"].find_first(|_| true).is_none());
+            }
+            Err(error) => {
+                assert!(false, "{}", error.to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn find_first_stops_early() {
+        match parse(JSON) {
+            Ok(parsed) => {
+                let mut tested = 0;
+                let found = parsed["items"].find_first(|item| {
+                    tested += 1;
+                    item.contains_key("type")
+                });
+                assert_eq!(found.map(|(path, _)| path.to_pointer().to_string()), Some("/0".to_owned()));
+                assert_eq!(tested, 2);
+            }
+            Err(error) => {
+                assert!(false, "{}", error.to_string());
+            }
+        }
+    }
+}
@@ -1,14 +1,17 @@
-use std::collections::{btree_map, BTreeMap};
+use std::iter::FusedIterator;
 use std::slice::Iter;
 
 use crate::generics::IterArray::{IterArrayEmpty, IterArrayVec};
-use crate::generics::IterMap::{IterMapBTree, IterMapEmpty, IterMapVec};
+use crate::generics::IterMap::{IterMapEmpty, IterMapVec};
 
 #[derive(Debug)]
+#[allow(clippy::box_collection)]
 pub(crate) enum Container<K, V> {
     Array(Vec<V>),
     MapVec(Vec<(K, V)>),
-    MapBTree(BTreeMap<K, V>),
+    // Entries in document order, and entry positions sorted by key for lookups. The index is boxed so that the
+    // variant is no larger than the others, only large objects paying for the extra allocation.
+    MapIndexed(Vec<(K, V)>, Box<Vec<usize>>),
 }
 
 pub(crate) enum IterArray<'a, V> {
@@ -30,9 +33,29 @@ impl<'a, V> Iterator for ArrayIterator<'a, V> {
             IterArrayVec(iter_vec) => { iter_vec.next() }
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.iter {
+            IterArrayEmpty() => { (0, Some(0)) }
+            IterArrayVec(iter_vec) => { iter_vec.size_hint() }
+        }
+    }
+}
+
+impl<V> DoubleEndedIterator for ArrayIterator<'_, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match &mut self.iter {
+            IterArrayEmpty() => { None }
+            IterArrayVec(iter_vec) => { iter_vec.next_back() }
+        }
+    }
 }
 
-impl<'a, V> ToOwned for ArrayIterator<'a, V> {
+impl<V> ExactSizeIterator for ArrayIterator<'_, V> {}
+
+impl<V> FusedIterator for ArrayIterator<'_, V> {}
+
+impl<V> ToOwned for ArrayIterator<'_, V> {
     type Owned = Self;
 
     fn to_owned(&self) -> Self::Owned {
@@ -46,7 +69,6 @@ impl<'a, V> ToOwned for ArrayIterator<'a, V> {
 pub(crate) enum IterMap<'a, K, V> {
     IterMapEmpty(),
     IterMapVec(Iter<'a, (K, V)>),
-    IterMapBTree(btree_map::Iter<'a, K, V>),
 }
 
 /// Object entries iterator
@@ -66,19 +88,91 @@ impl<'a, K, V> Iterator for MapIterator<'a, K, V> {
                     Some((k, v)) => { Some((k, v)) }
                 }
             }
-            IterMapBTree(iter_map) => { iter_map.next() }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.iter {
+            IterMapEmpty() => { (0, Some(0)) }
+            IterMapVec(iter_vec) => { iter_vec.size_hint() }
         }
     }
 }
 
-impl<'a, K, V> ToOwned for MapIterator<'a, K, V> {
+impl<K, V> DoubleEndedIterator for MapIterator<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match &mut self.iter {
+            IterMapEmpty() => { None }
+            IterMapVec(iter_vec) => { iter_vec.next_back().map(|(k, v)| (k, v)) }
+        }
+    }
+}
+
+impl<K, V> ExactSizeIterator for MapIterator<'_, K, V> {}
+
+impl<K, V> FusedIterator for MapIterator<'_, K, V> {}
+
+impl<K, V> ToOwned for MapIterator<'_, K, V> {
     type Owned = Self;
 
     fn to_owned(&self) -> Self::Owned {
         match &self.iter {
             IterMapEmpty() => { MapIterator { iter: IterMapEmpty() } }
             IterMapVec(iter_vec) => { MapIterator { iter: IterMapVec(iter_vec.to_owned()) } }
-            IterMapBTree(iter_btree) => { MapIterator { iter: IterMapBTree(iter_btree.to_owned()) } }
         }
     }
-}
\ No newline at end of file
+}
+
+/// Object keys iterator
+pub struct KeyIterator<'a, K, V> {
+    pub(crate) iter: MapIterator<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for KeyIterator<'a, K, V> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(k, _)| k)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for KeyIterator<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|(k, _)| k)
+    }
+}
+
+impl<K, V> ExactSizeIterator for KeyIterator<'_, K, V> {}
+
+impl<K, V> FusedIterator for KeyIterator<'_, K, V> {}
+
+/// Object values iterator
+pub struct ValueIterator<'a, K, V> {
+    pub(crate) iter: MapIterator<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for ValueIterator<'a, K, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(_, v)| v)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for ValueIterator<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|(_, v)| v)
+    }
+}
+
+impl<K, V> ExactSizeIterator for ValueIterator<'_, K, V> {}
+
+impl<K, V> FusedIterator for ValueIterator<'_, K, V> {}
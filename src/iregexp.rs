@@ -0,0 +1,574 @@
+// Interoperable regular expressions (I-Regexp, RFC 9485), as used by the JSONPath `match` and `search` functions.
+// Patterns are compiled to a small instruction set and run by a Pike VM: matching time is linear in the length of the
+// input, whatever the pattern.
+
+// Patterns expanding to more instructions than this (through counted repetitions) are rejected, as are repetition
+// counts above it
+const MAX_INSTRUCTIONS: usize = 10_000;
+// Groups nesting limit, so that parsing and compiling are not limited by the call stack
+const MAX_NESTING: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Category {
+    Letter,
+    UppercaseLetter,
+    LowercaseLetter,
+    Number,
+    DecimalNumber,
+    Control,
+    Separator,
+    SpaceSeparator,
+    LineSeparator,
+    ParagraphSeparator,
+}
+
+impl Category {
+    // Only categories that the standard library can decide are supported. Letters and numbers are approximated with
+    // its Alphabetic, Uppercase, Lowercase and Numeric properties.
+    fn new(name: &str) -> Option<Self> {
+        match name {
+            "L" => { Some(Category::Letter) }
+            "Lu" => { Some(Category::UppercaseLetter) }
+            "Ll" => { Some(Category::LowercaseLetter) }
+            "N" => { Some(Category::Number) }
+            "Nd" => { Some(Category::DecimalNumber) }
+            "Cc" => { Some(Category::Control) }
+            "Z" => { Some(Category::Separator) }
+            "Zs" => { Some(Category::SpaceSeparator) }
+            "Zl" => { Some(Category::LineSeparator) }
+            "Zp" => { Some(Category::ParagraphSeparator) }
+            _ => { None }
+        }
+    }
+
+    fn contains(self, c: char) -> bool {
+        match self {
+            Category::Letter => { c.is_alphabetic() }
+            Category::UppercaseLetter => { c.is_uppercase() }
+            Category::LowercaseLetter => { c.is_lowercase() }
+            Category::Number => { c.is_numeric() }
+            Category::DecimalNumber => { c.is_numeric() && (c.is_ascii_digit() || !c.is_ascii()) }
+            Category::Control => { c.is_control() }
+            Category::Separator => { is_space_separator(c) || c == '\u{2028}' || c == '\u{2029}' }
+            Category::SpaceSeparator => { is_space_separator(c) }
+            Category::LineSeparator => { c == '\u{2028}' }
+            Category::ParagraphSeparator => { c == '\u{2029}' }
+        }
+    }
+}
+
+fn is_space_separator(c: char) -> bool {
+    matches!(c, ' ' | '\u{a0}' | '\u{1680}' | '\u{2000}'..='\u{200a}' | '\u{202f}' | '\u{205f}' | '\u{3000}')
+}
+
+#[derive(Debug, Clone)]
+enum ClassItem {
+    Range(char, char),
+    Category(Category, bool),
+}
+
+#[derive(Debug, Clone)]
+struct CharClass {
+    negated: bool,
+    items: Vec<ClassItem>,
+}
+
+impl CharClass {
+    fn contains(&self, c: char) -> bool {
+        let found = self.items.iter().any(|item| {
+            match item {
+                ClassItem::Range(low, high) => { (*low..=*high).contains(&c) }
+                ClassItem::Category(category, negated) => { category.contains(c) != *negated }
+            }
+        });
+        found != self.negated
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Empty,
+    Char(char),
+    // Any character but line feed and carriage return
+    Any,
+    Class(CharClass),
+    Concat(Vec<Node>),
+    Alternation(Vec<Node>),
+    Repeat(Box<Node>, u32, Option<u32>),
+}
+
+#[derive(Debug, Clone)]
+enum Instruction {
+    Char(char),
+    Any,
+    Class(CharClass),
+    Split(usize, usize),
+    Jump(usize),
+    Match,
+}
+
+// A compiled I-Regexp
+#[derive(Debug, Clone)]
+pub(crate) struct Regex {
+    program: Vec<Instruction>,
+}
+
+impl Regex {
+    // Returns `None` if the pattern is not a valid I-Regexp, or if it is too large
+    pub(crate) fn new(pattern: &str) -> Option<Self> {
+        let chars: Vec<char> = pattern.chars().collect();
+        let mut parser = Parser { chars: &chars, index: 0, depth: 0 };
+        let node = parser.alternation()?;
+        if parser.index != chars.len() {
+            return None;
+        }
+        let mut program = Vec::new();
+        compile(&node, &mut program)?;
+        program.push(Instruction::Match);
+        Some(Regex { program })
+    }
+
+    // Checks if the whole text matches
+    pub(crate) fn is_match(&self, text: &str) -> bool {
+        self.run(text, true)
+    }
+
+    // Checks if some substring of the text matches
+    pub(crate) fn is_found(&self, text: &str) -> bool {
+        self.run(text, false)
+    }
+
+    fn run(&self, text: &str, anchored: bool) -> bool {
+        let mut current = Threads::new(self.program.len());
+        let mut next = Threads::new(self.program.len());
+        self.add_thread(&mut current, 0);
+        for c in text.chars() {
+            if !anchored {
+                if current.contains_match(&self.program) {
+                    return true;
+                }
+                self.add_thread(&mut current, 0);
+            }
+            for &pc in &current.list {
+                let matched = match &self.program[pc] {
+                    Instruction::Char(expected) => { *expected == c }
+                    Instruction::Any => { c != '\n' && c != '\r' }
+                    Instruction::Class(class) => { class.contains(c) }
+                    _ => { false }
+                };
+                if matched {
+                    self.add_thread(&mut next, pc + 1);
+                }
+            }
+            std::mem::swap(&mut current, &mut next);
+            next.clear();
+            if anchored && current.list.is_empty() {
+                return false;
+            }
+        }
+        if !anchored {
+            self.add_thread(&mut current, 0);
+        }
+        current.contains_match(&self.program)
+    }
+
+    // Adds a thread and follows its jumps and splits
+    fn add_thread(&self, threads: &mut Threads, pc: usize) {
+        let mut pending = vec![pc];
+        while let Some(pc) = pending.pop() {
+            if threads.seen[pc] {
+                continue;
+            }
+            threads.seen[pc] = true;
+            threads.marked.push(pc);
+            match self.program[pc] {
+                Instruction::Jump(target) => { pending.push(target); }
+                Instruction::Split(first, second) => {
+                    pending.push(second);
+                    pending.push(first);
+                }
+                _ => { threads.list.push(pc); }
+            }
+        }
+    }
+}
+
+// Threads waiting on a character or on the end of the match, in priority order. Jumps and splits are followed
+// as threads are added, and only marked as seen.
+struct Threads {
+    list: Vec<usize>,
+    marked: Vec<usize>,
+    seen: Vec<bool>,
+}
+
+impl Threads {
+    fn new(size: usize) -> Self {
+        Threads { list: Vec::new(), marked: Vec::new(), seen: vec![false; size] }
+    }
+
+    fn clear(&mut self) {
+        for &pc in &self.marked {
+            self.seen[pc] = false;
+        }
+        self.marked.clear();
+        self.list.clear();
+    }
+
+    fn contains_match(&self, program: &[Instruction]) -> bool {
+        self.list.iter().any(|&pc| matches!(program[pc], Instruction::Match))
+    }
+}
+
+fn compile(node: &Node, program: &mut Vec<Instruction>) -> Option<()> {
+    if program.len() > MAX_INSTRUCTIONS {
+        return None;
+    }
+    match node {
+        Node::Empty => {}
+        Node::Char(c) => { program.push(Instruction::Char(*c)); }
+        Node::Any => { program.push(Instruction::Any); }
+        Node::Class(class) => { program.push(Instruction::Class(class.clone())); }
+        Node::Concat(nodes) => {
+            for node in nodes {
+                compile(node, program)?;
+            }
+        }
+        Node::Alternation(nodes) => {
+            let mut jumps = Vec::new();
+            for (i, node) in nodes.iter().enumerate() {
+                if i + 1 < nodes.len() {
+                    let split = program.len();
+                    program.push(Instruction::Split(split + 1, 0));
+                    compile(node, program)?;
+                    jumps.push(program.len());
+                    program.push(Instruction::Jump(0));
+                    program[split] = Instruction::Split(split + 1, program.len());
+                } else {
+                    compile(node, program)?;
+                }
+            }
+            let end = program.len();
+            for jump in jumps {
+                program[jump] = Instruction::Jump(end);
+            }
+        }
+        // A repetition only matching the empty string needs no instruction. Skipping it keeps compiling time bounded by
+        // the instructions emitted, even for large counts of zero-width bodies.
+        Node::Repeat(node, _, max) if *max == Some(0) || zero_width(node) => {}
+        Node::Repeat(node, min, max) => {
+            for _ in 0..*min {
+                compile(node, program)?;
+            }
+            match max {
+                None => {
+                    let split = program.len();
+                    program.push(Instruction::Split(split + 1, 0));
+                    compile(node, program)?;
+                    program.push(Instruction::Jump(split));
+                    program[split] = Instruction::Split(split + 1, program.len());
+                }
+                Some(max) => {
+                    let mut splits = Vec::new();
+                    for _ in *min..*max {
+                        splits.push(program.len());
+                        program.push(Instruction::Split(0, 0));
+                        compile(node, program)?;
+                    }
+                    let end = program.len();
+                    for split in splits {
+                        program[split] = Instruction::Split(split + 1, end);
+                    }
+                }
+            }
+        }
+    }
+    if program.len() > MAX_INSTRUCTIONS {
+        None
+    } else {
+        Some(())
+    }
+}
+
+// Whether a node only matches the empty string
+fn zero_width(node: &Node) -> bool {
+    match node {
+        Node::Empty => { true }
+        Node::Char(_) | Node::Any | Node::Class(_) => { false }
+        Node::Concat(nodes) | Node::Alternation(nodes) => { nodes.iter().all(zero_width) }
+        Node::Repeat(node, _, max) => { *max == Some(0) || zero_width(node) }
+    }
+}
+
+struct Parser<'c> {
+    chars: &'c [char],
+    index: usize,
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.index).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.index += 1;
+        Some(c)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    // i-regexp = branch *( "|" branch )
+    fn alternation(&mut self) -> Option<Node> {
+        let mut branches = vec![self.branch()?];
+        while self.eat('|') {
+            branches.push(self.branch()?);
+        }
+        Some(if branches.len() == 1 { branches.pop()? } else { Node::Alternation(branches) })
+    }
+
+    // branch = *piece, piece = atom [ quantifier ]
+    fn branch(&mut self) -> Option<Node> {
+        let mut pieces = Vec::new();
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            let atom = self.atom()?;
+            pieces.push(self.quantifier(atom)?);
+        }
+        Some(match pieces.len() {
+            0 => { Node::Empty }
+            1 => { pieces.pop()? }
+            _ => { Node::Concat(pieces) }
+        })
+    }
+
+    fn quantifier(&mut self, atom: Node) -> Option<Node> {
+        let (min, max) = match self.peek() {
+            Some('*') => { (0, None) }
+            Some('+') => { (1, None) }
+            Some('?') => { (0, Some(1)) }
+            Some('{') => {
+                self.index += 1;
+                let min = self.quantity()?;
+                let max = if self.eat(',') {
+                    if self.peek() == Some('}') { None } else { Some(self.quantity()?) }
+                } else {
+                    Some(min)
+                };
+                if self.peek() != Some('}') || max.is_some_and(|max| max < min) || max.unwrap_or(min) as usize > MAX_INSTRUCTIONS {
+                    return None;
+                }
+                (min, max)
+            }
+            _ => { return Some(atom); }
+        };
+        self.index += 1;
+        Some(Node::Repeat(Box::new(atom), min, max))
+    }
+
+    fn quantity(&mut self) -> Option<u32> {
+        let start = self.index;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.index += 1;
+        }
+        if start == self.index {
+            return None;
+        }
+        self.chars[start..self.index].iter().collect::<String>().parse::<u32>().ok()
+    }
+
+    // atom = NormalChar / charClass / ( "(" i-regexp ")" )
+    fn atom(&mut self) -> Option<Node> {
+        match self.next()? {
+            '(' => {
+                if self.depth == MAX_NESTING {
+                    return None;
+                }
+                self.depth += 1;
+                let node = self.alternation()?;
+                self.depth -= 1;
+                if !self.eat(')') {
+                    return None;
+                }
+                Some(node)
+            }
+            '.' => { Some(Node::Any) }
+            '[' => { self.class_expression() }
+            '\\' => {
+                match self.escape()? {
+                    Escape::Char(c) => { Some(Node::Char(c)) }
+                    Escape::Category(category, negated) => {
+                        Some(Node::Class(CharClass { negated: false, items: vec![ClassItem::Category(category, negated)] }))
+                    }
+                }
+            }
+            ')' | '*' | '+' | '?' | ']' | '{' | '}' | '|' => { None }
+            c => { Some(Node::Char(c)) }
+        }
+    }
+
+    // Escapes, after the backslash: SingleCharEsc or charClassEsc
+    fn escape(&mut self) -> Option<Escape> {
+        match self.next()? {
+            c @ ('(' | ')' | '*' | '+' | ',' | '-' | '.' | '?' | '[' | '\\' | ']' | '^' | '{' | '|' | '}') => { Some(Escape::Char(c)) }
+            'n' => { Some(Escape::Char('\n')) }
+            'r' => { Some(Escape::Char('\r')) }
+            't' => { Some(Escape::Char('\t')) }
+            c @ ('p' | 'P') => {
+                if !self.eat('{') {
+                    return None;
+                }
+                let start = self.index;
+                while self.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+                    self.index += 1;
+                }
+                let name: String = self.chars[start..self.index].iter().collect();
+                if !self.eat('}') {
+                    return None;
+                }
+                Some(Escape::Category(Category::new(&name)?, c == 'P'))
+            }
+            _ => { None }
+        }
+    }
+
+    // charClassExpr = "[" [ "^" ] ( "-" / CCE1 ) *CCE1 [ "-" ] "]", after the opening bracket
+    fn class_expression(&mut self) -> Option<Node> {
+        let negated = self.eat('^');
+        let mut items = Vec::new();
+        if self.eat('-') {
+            items.push(ClassItem::Range('-', '-'));
+        }
+        loop {
+            match self.peek()? {
+                ']' => {
+                    self.index += 1;
+                    break;
+                }
+                '-' => {
+                    // A hyphen is only allowed last
+                    self.index += 1;
+                    if !self.eat(']') {
+                        return None;
+                    }
+                    items.push(ClassItem::Range('-', '-'));
+                    break;
+                }
+                _ => {
+                    match self.class_char()? {
+                        Escape::Category(category, negated) => { items.push(ClassItem::Category(category, negated)); }
+                        Escape::Char(low) => {
+                            if self.peek() == Some('-') && self.chars.get(self.index + 1).is_some_and(|c| *c != ']') {
+                                self.index += 1;
+                                match self.class_char()? {
+                                    Escape::Char(high) if low <= high => { items.push(ClassItem::Range(low, high)); }
+                                    _ => { return None; }
+                                }
+                            } else {
+                                items.push(ClassItem::Range(low, low));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Some(Node::Class(CharClass { negated, items }))
+    }
+
+    // CCchar, or a category escape
+    fn class_char(&mut self) -> Option<Escape> {
+        match self.next()? {
+            '\\' => { self.escape() }
+            '[' | ']' | '-' => { None }
+            c => { Some(Escape::Char(c)) }
+        }
+    }
+}
+
+enum Escape {
+    Char(char),
+    Category(Category, bool),
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::iregexp::Regex;
+
+    #[test]
+    fn full_match() {
+        let cases = [
+            ("a.c", "abc", true),
+            ("a.c", "a\nc", false),
+            ("ab*c", "ac", true),
+            ("ab+c", "ac", false),
+            ("(ab|cd)+", "abcdab", true),
+            ("(ab|cd)+", "abc", false),
+            ("a{2,3}", "aaa", true),
+            ("a{2,3}", "aaaa", false),
+            ("a{2,}", "aaaaa", true),
+            ("a{2}", "a", false),
+            ("[a-c]+", "abcab", true),
+            ("[^a-c]", "d", true),
+            ("[^a-c]", "a", false),
+            ("[-a]+", "-a-", true),
+            ("[a-]+", "-a-", true),
+            ("\\p{Lu}\\p{Ll}*", "Hello", true),
+            ("\\P{L}+", "12", true),
+            ("[\\p{Nd}.]+", "1.5", true),
+            ("\\.\\*", ".*", true),
+            ("^a$", "^a$", true),
+            ("", "", true),
+            ("x?", "", true),
+            ("日本.", "日本語", true),
+        ];
+        for (pattern, text, expected) in cases {
+            let regex = Regex::new(pattern).unwrap_or_else(|| panic!("{}", pattern));
+            assert_eq!(regex.is_match(text), expected, "{} {}", pattern, text);
+        }
+    }
+
+    #[test]
+    fn search() {
+        let regex = Regex::new("b.r").unwrap();
+        assert!(regex.is_found("foobarbaz"));
+        assert!(!regex.is_found("foobaz"));
+        assert!(Regex::new("").unwrap().is_found("abc"));
+        assert!(Regex::new("c$").unwrap().is_found("abc$"));
+    }
+
+    #[test]
+    fn invalid() {
+        for pattern in ["(", "a)", "[a", "a{2,1}", "*", "a**", "\\d", "\\p{Xx}", "[b-a]", "a{", "[a-b-c]", "(?:a)"] {
+            assert!(Regex::new(pattern).is_none(), "{}", pattern);
+        }
+        assert!(Regex::new("(a{1000}){1000}").is_none());
+        assert!(Regex::new(&("(".repeat(100) + &")".repeat(100))).is_some());
+        assert!(Regex::new(&("(".repeat(101) + &")".repeat(101))).is_none());
+        assert!(Regex::new(&"(".repeat(200_000)).is_none());
+    }
+
+    #[test]
+    fn linear_time() {
+        let regex = Regex::new("(a*)*b").unwrap();
+        assert!(!regex.is_match(&"a".repeat(10_000)));
+    }
+
+    #[test]
+    fn bounded_compiling() {
+        assert!(Regex::new("(){4294967295}").is_none());
+        assert!(Regex::new("a{0,10001}").is_none());
+        let regex = Regex::new("((((){10000}){10000}){10000}){10000}b").unwrap();
+        assert!(regex.is_match("b") && !regex.is_match("ab"));
+        assert!(Regex::new("(a{0}){10000}(|()*){10000}b").is_some_and(|regex| regex.is_match("b")));
+        assert!(Regex::new("((a){100}){101}").is_none());
+    }
+}
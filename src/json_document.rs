@@ -0,0 +1,257 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::fmt::{Debug, Formatter};
+use std::mem::ManuallyDrop;
+use std::ops::Index;
+
+use crate::encoding::transcode;
+use crate::json_error::JsonError;
+use crate::json_item::JsonItem;
+use crate::json_pointer::JsonPointer;
+use crate::parse_options::ParseOptions;
+
+/// An owned JSON document, keeping its source text alive alongside the parsed tree.
+/// Unlike [`crate::parse`], the result has no lifetime attached, so it can be returned from functions,
+/// stored in collections or sent to other threads. Items are reached through [`JsonDocument::root`], or by indexing
+/// the document. Items point into the document's own buffer, so they must not outlive it: indexing yields a
+/// [`DocumentItem`] rather than a `JsonItem<'static>`, the item being handed out through [`DocumentItem::item`] with
+/// a lifetime bounded by the document.
+///
+/// ```rust
+/// use jsonic::json_document::JsonDocument;
+///
+/// fn load() -> JsonDocument {
+///     let text = String::from("{\"a\": [1, 2, 3]}");
+///     JsonDocument::new(text).unwrap()
+/// }
+///
+/// let document = load();
+/// assert_eq!(document.root()["a"][1].as_i128(), Some(2));
+/// assert_eq!(document["a"][1].item().as_i128(), Some(2));
+/// ```
+pub struct JsonDocument {
+    root: ManuallyDrop<JsonItem<'static>>,
+    source: *mut str,
+}
+
+impl JsonDocument {
+    /// Parses a JSON document, taking ownership of its source text
+    pub fn new<S: Into<Box<str>>>(source: S) -> Result<Self, JsonError> {
+        Self::new_with(source, &ParseOptions::new())
+    }
+
+    /// Parses a JSON document using the provided options, taking ownership of its source text
+    pub fn new_with<S: Into<Box<str>>>(source: S, options: &ParseOptions) -> Result<Self, JsonError> {
+        // The heap buffer is never moved or mutated while the document lives, so items can point into it
+        let source = Box::into_raw(source.into());
+        match crate::parse_with(unsafe { &*source }, options) {
+            Ok(root) => { Ok(JsonDocument { root: ManuallyDrop::new(root), source }) }
+            Err(error) => {
+                drop(unsafe { Box::from_raw(source) });
+                Err(error)
+            }
+        }
+    }
+
+    /// Parses a JSON document from raw bytes, taking ownership of them.
+    /// UTF-16 and UTF-32 data is detected and transcoded to UTF-8 first, see [`crate::encoding::transcode`].
+    pub fn from_bytes<B: Into<Vec<u8>>>(bytes: B) -> Result<Self, JsonError> {
+        Self::from_bytes_with(bytes, &ParseOptions::new())
+    }
+
+    /// Parses a JSON document from raw bytes using the provided options, taking ownership of them. See [`JsonDocument::from_bytes`].
+    pub fn from_bytes_with<B: Into<Vec<u8>>>(bytes: B, options: &ParseOptions) -> Result<Self, JsonError> {
+        let bytes = bytes.into();
+        let transcoded = match transcode(&bytes)? {
+            Cow::Owned(text) => { Some(text) }
+            Cow::Borrowed(_) => { None }
+        };
+        match transcoded {
+            Some(text) => { Self::new_with(text, options) }
+            // Already validated by transcode
+            None => { Self::new_with(unsafe { String::from_utf8_unchecked(bytes) }, options) }
+        }
+    }
+
+    /// Returns the root item of the document, borrowing from it
+    ///
+    /// ```rust,compile_fail
+    /// use jsonic::json_document::JsonDocument;
+    /// use jsonic::json_item::JsonItem;
+    ///
+    /// let document = JsonDocument::new("[1]").unwrap();
+    /// let item: &JsonItem<'static> = &document.root()[0];
+    /// ```
+    pub fn root(&self) -> &JsonItem<'_> {
+        &self.root
+    }
+
+    /// Returns the source text of the document
+    pub fn source(&self) -> &str {
+        unsafe { &*self.source }
+    }
+
+    /// Returns the innermost item at a byte offset in the source text, along with its path. See [`JsonItem::locate`].
+    pub fn locate(&self, offset: usize) -> Option<(JsonPointer, &JsonItem<'_>)> {
+        self.root.locate(self.source(), offset)
+    }
+
+    /// Returns the path from the root to `target`, an item borrowed from the document. See [`JsonItem::path_of`].
+    pub fn path_of(&self, target: &JsonItem<'_>) -> Option<JsonPointer> {
+        self.root.path_of(target)
+    }
+}
+
+impl Drop for JsonDocument {
+    fn drop(&mut self) {
+        // Items must go before the text they point into
+        unsafe {
+            ManuallyDrop::drop(&mut self.root);
+            drop(Box::from_raw(self.source));
+        }
+    }
+}
+
+// The document is immutable once parsed, and owns everything its items point to
+unsafe impl Send for JsonDocument {}
+
+unsafe impl Sync for JsonDocument {}
+
+impl Index<usize> for JsonDocument {
+    type Output = DocumentItem;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        DocumentItem::wrap(&self.root[index])
+    }
+}
+
+impl Index<&str> for JsonDocument {
+    type Output = DocumentItem;
+
+    fn index(&self, key: &str) -> &Self::Output {
+        DocumentItem::wrap(&self.root[key])
+    }
+}
+
+/// An item of a [`JsonDocument`], as returned by indexing it. Indexing can be chained, and the item itself is
+/// reached through [`DocumentItem::item`], borrowing from the document.
+///
+/// ```rust,compile_fail
+/// use jsonic::json_document::JsonDocument;
+///
+/// let text = {
+///     let document = JsonDocument::new("[\"a\"]").unwrap();
+///     document[0].item().as_str()
+/// };
+/// ```
+#[repr(transparent)]
+pub struct DocumentItem(JsonItem<'static>);
+
+impl DocumentItem {
+    // The wrapper is transparent, and only ever handed out behind a reference to the document
+    fn wrap<'d>(item: &'d JsonItem<'static>) -> &'d Self {
+        unsafe { &*(item as *const JsonItem<'static> as *const DocumentItem) }
+    }
+
+    /// Returns the item, with a lifetime bounded by the document
+    pub fn item(&self) -> &JsonItem<'_> {
+        &self.0
+    }
+}
+
+impl Index<usize> for DocumentItem {
+    type Output = DocumentItem;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        DocumentItem::wrap(&self.0[index])
+    }
+}
+
+impl Index<&str> for DocumentItem {
+    type Output = DocumentItem;
+
+    fn index(&self, key: &str) -> &Self::Output {
+        DocumentItem::wrap(&self.0[key])
+    }
+}
+
+impl Debug for DocumentItem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.0, f)
+    }
+}
+
+impl Debug for JsonDocument {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&*self.root, f)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants)]
+mod tests {
+    use std::collections::HashMap;
+    use std::thread;
+
+    use crate::json_document::JsonDocument;
+    use crate::json_error::JsonErrorKind;
+    use crate::parse_options::{DuplicateKeys, ParseOptions};
+
+    const JSON: &str = "{\"name\": \"jsonic\", \"tags\": [\"fast\", \"small\"]}";
+
+    #[test]
+    fn owned_from_string() {
+        match JsonDocument::new(JSON.to_string()) {
+            Ok(document) => {
+                assert_eq!(document.root()["name"].as_str(), Some("jsonic"));
+                assert_eq!(document["tags"][1].item().as_str(), Some("small"));
+                assert!(document["missing"][0].item().is_empty());
+                assert_eq!(document.source(), JSON);
+                let (path, item) = document.locate(JSON.find("small").unwrap()).unwrap();
+                assert_eq!((path.to_string().as_str(), item.as_str()), ("/tags/1", Some("small")));
+                assert_eq!(document.path_of(document["tags"][0].item()).map(|path| path.to_string()), Some("/tags/0".to_owned()));
+            }
+            Err(error) => {
+                assert!(false, "{}", error.to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn owned_from_bytes() {
+        match JsonDocument::from_bytes(JSON.as_bytes().to_vec()) {
+            Ok(document) => {
+                assert_eq!(document.root()["tags"][0].as_str(), Some("fast"));
+            }
+            Err(error) => {
+                assert!(false, "{}", error.to_string());
+            }
+        }
+        match JsonDocument::from_bytes(vec![b'[', b'"', 0xff, b'"', b']']) {
+            Ok(_) => { assert!(false); }
+            Err(error) => { assert_eq!(error.get_index(), 2); }
+        }
+        let utf16: Vec<u8> = "\u{feff}{\"a\": \"ü\", \"a\": 1}".encode_utf16().flat_map(u16::to_be_bytes).collect();
+        match JsonDocument::from_bytes(utf16.clone()) {
+            Ok(document) => {
+                assert_eq!(document.root()["a"].as_str(), Some("ü"));
+            }
+            Err(error) => {
+                assert!(false, "{}", error.to_string());
+            }
+        }
+        match JsonDocument::from_bytes_with(utf16, &ParseOptions::new().duplicate_keys(DuplicateKeys::Error)) {
+            Ok(_) => { assert!(false); }
+            Err(error) => { assert_eq!(error.get_kind(), &JsonErrorKind::DuplicateKey("a".to_owned())); }
+        }
+    }
+
+    #[test]
+    fn cache_and_send() {
+        let mut cache = HashMap::new();
+        cache.insert("doc", JsonDocument::new(JSON).unwrap());
+        let document = cache.remove("doc").unwrap();
+        let name = thread::spawn(move || document["name"].item().as_str().map(str::to_owned)).join().unwrap();
+        assert_eq!(name.as_deref(), Some("jsonic"));
+    }
+}
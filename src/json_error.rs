@@ -4,15 +4,78 @@ use std::error::Error;
 
 const EXTRACT_PADDING: usize = 8;
 
+/// Kinds of parsing errors
+#[derive(PartialEq, Debug, Clone)]
+#[non_exhaustive]
+pub enum JsonErrorKind {
+    /// Unexpected character or premature end of data
+    Syntax,
+    /// Invalid UTF-8 byte sequence, or source data in an unsupported encoding
+    InvalidUtf8,
+    /// Number not following the JSON number grammar
+    InvalidNumber,
+    /// Unknown escape sequence in a string, or `\u` escape not followed by 4 hexadecimal digits
+    InvalidEscape,
+    /// Unescaped control character (U+0000 to U+001F) in a string
+    ControlCharacter,
+    /// Non-whitespace content after the root value
+    TrailingCharacters,
+    /// Key found twice in the same object, when duplicates are not allowed
+    DuplicateKey(String),
+    /// Source data larger than [`crate::parse_options::ParseOptions::max_input_size`]
+    InputTooLarge,
+    /// Nesting deeper than [`crate::parse_options::ParseOptions::max_depth`]
+    DepthLimitExceeded,
+    /// String longer than [`crate::parse_options::ParseOptions::max_string_length`]
+    StringTooLong,
+    /// Array longer than [`crate::parse_options::ParseOptions::max_array_length`]
+    ArrayTooLong,
+    /// Object with more entries than [`crate::parse_options::ParseOptions::max_object_entries`]
+    ObjectTooLarge,
+    /// More items in the document than [`crate::parse_options::ParseOptions::max_nodes`]
+    TooManyNodes,
+    /// JSON Pointer not starting with `/`, or containing a `~` not followed by `0` or `1`
+    InvalidPointer,
+    /// JSONPath query not following the RFC 9535 grammar, or with ill-typed function calls
+    InvalidJsonPath,
+}
+
+impl Display for JsonErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            JsonErrorKind::Syntax => { write!(f, "JSON error") }
+            JsonErrorKind::InvalidUtf8 => { write!(f, "Invalid UTF-8 sequence") }
+            JsonErrorKind::InvalidNumber => { write!(f, "Invalid number") }
+            JsonErrorKind::InvalidEscape => { write!(f, "Invalid escape sequence") }
+            JsonErrorKind::ControlCharacter => { write!(f, "Unescaped control character") }
+            JsonErrorKind::TrailingCharacters => { write!(f, "Trailing characters") }
+            JsonErrorKind::DuplicateKey(key) => { write!(f, "Duplicate key '{}'", key) }
+            JsonErrorKind::InputTooLarge => { write!(f, "Input size limit exceeded") }
+            JsonErrorKind::DepthLimitExceeded => { write!(f, "Nesting depth limit exceeded") }
+            JsonErrorKind::StringTooLong => { write!(f, "String length limit exceeded") }
+            JsonErrorKind::ArrayTooLong => { write!(f, "Array length limit exceeded") }
+            JsonErrorKind::ObjectTooLarge => { write!(f, "Object entries limit exceeded") }
+            JsonErrorKind::TooManyNodes => { write!(f, "Node count limit exceeded") }
+            JsonErrorKind::InvalidPointer => { write!(f, "Invalid JSON pointer") }
+            JsonErrorKind::InvalidJsonPath => { write!(f, "Invalid JSONPath query") }
+        }
+    }
+}
+
 /// Parsing errors
 #[derive(Debug)]
 pub struct JsonError {
     index: usize,
     extract: Option<String>,
+    kind: JsonErrorKind,
 }
 
 impl JsonError {
     pub(crate) fn new(bytes: &[u8], index: usize) -> Self {
+        Self::with_kind(bytes, index, JsonErrorKind::Syntax)
+    }
+
+    pub(crate) fn with_kind(bytes: &[u8], index: usize, kind: JsonErrorKind) -> Self {
         let extract = match from_utf8(&bytes[isize::max(0, index as isize - EXTRACT_PADDING as isize) as usize..usize::min(bytes.len(), index + EXTRACT_PADDING)]) {
             Ok(extract) => { Some(extract.to_owned()) }
             Err(_) => { None }
@@ -20,6 +83,7 @@ impl JsonError {
         JsonError {
             index,
             extract,
+            kind,
         }
     }
 
@@ -33,13 +97,18 @@ impl JsonError {
     pub fn get_extract(&self) -> &Option<String> {
         &self.extract
     }
+
+    /// Returns the kind of error
+    pub fn get_kind(&self) -> &JsonErrorKind {
+        &self.kind
+    }
 }
 
 impl Display for JsonError {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         match &self.extract {
-            Some(extract) => { write!(f, "JSON error near '{}': index {} in data", extract, self.index) }
-            None => { write!(f, "JSON error at index {} in data", self.index) }
+            Some(extract) => { write!(f, "{} near '{}': index {} in data", self.kind, extract, self.index) }
+            None => { write!(f, "{} at index {} in data", self.kind, self.index) }
         }
     }
 }
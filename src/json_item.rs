@@ -1,60 +1,273 @@
-use std::ops::Index;
+use std::borrow::Cow;
+use std::fmt;
+use std::fmt::{Debug, Formatter};
+use std::io;
+use std::io::ErrorKind::InvalidInput;
+use std::io::Write;
+use std::mem::{replace, transmute};
+use std::ops::{ControlFlow, Index, Range};
+use std::ptr::addr_eq;
+use std::str::FromStr;
 
-use crate::generics::{ArrayIterator, Container, MapIterator};
-use crate::generics::Container::{Array, MapBTree, MapVec};
+use crate::find::Finder;
+use crate::generics::{ArrayIterator, Container, KeyIterator, MapIterator, ValueIterator};
+use crate::generics::Container::{Array, MapIndexed, MapVec};
 use crate::generics::IterArray::{IterArrayEmpty, IterArrayVec};
-use crate::generics::IterMap::{IterMapBTree, IterMapEmpty, IterMapVec};
+use crate::generics::IterMap::{IterMapEmpty, IterMapVec};
 use crate::json_type::JsonType;
-use crate::json_type::JsonType::{Empty, JsonArray, JsonFalse, JsonMap, JsonNull, JsonNumber, JsonTrue};
+use crate::json_type::JsonType::{Empty, JsonArray, JsonFalse, JsonMap, JsonNull, JsonNumber, JsonString, JsonTrue};
+use crate::json_pointer::{resolve_token, unescape_token, JsonPointer};
+use crate::jsonpath::NormalizedPath;
 use crate::key::Key;
+use crate::number::{is_exact, parse_small_integer, to_f64, DecodedNumber, JsonNumberValue, NumberKind, NumberLayout, NumberParts};
+use crate::parse_options::DuplicateKeys;
+use crate::parse_options::DuplicateKeys::{Error, FirstWins, LastWins};
 use crate::slice::Slice;
+use crate::unescape::{unescape, unescape_str, LoneSurrogates};
+use crate::visitor::{accept, Visitor};
+use crate::walk::Walker;
 
 const KEEP_VEC_THRESHOLD: usize = 64;
 
-static EMPTY_ITEM: JsonItem = JsonItem::empty();
+static EMPTY_ITEM: JsonItem<'static> = JsonItem::empty();
 
 /// Container for a JSON element, i.e. can contain a JSON null, bool, string, number, object or array.
+/// The item borrows from the source text it was parsed from, and cannot outlive it.
 #[derive(Debug)]
-pub struct JsonItem {
-    pub(crate) slice: Slice,
+pub struct JsonItem<'a> {
+    pub(crate) slice: Slice<'a>,
     pub(crate) json_type: JsonType,
-    container: Option<Container<Key, JsonItem>>,
+    payload: Payload,
 }
 
-impl JsonItem {
-    pub(crate) fn new(slice: Slice, json_type: JsonType) -> Self {
-        JsonItem { slice, json_type, container: None }
+// Data kept alongside the slice, depending on the item type. Decoded numbers get variants of their own rather than
+// an `Option<DecodedNumber>`, which keeps items as small as they are without it.
+#[derive(Debug)]
+enum Payload {
+    None,
+    Children(Children),
+    Number(NumberLayout),
+    Signed(NumberLayout, i64),
+    Unsigned(NumberLayout, u64),
+    Float(NumberLayout, f64),
+}
+
+impl Payload {
+    fn take_children(&mut self) -> Option<Children> {
+        match replace(self, Payload::None) {
+            Payload::Children(children) => { Some(children) }
+            payload => {
+                *self = payload;
+                None
+            }
+        }
+    }
+}
+
+// Children are stored with an erased lifetime: a `Drop` implementation on `JsonItem<'a>` itself would make drop check
+// require the source text to strictly outlive every item. They are only ever handed out as `JsonItem<'a>`, see `container()`.
+struct Children(Container<Key<'static>, JsonItem<'static>>);
+
+impl Children {
+    fn new<'a>(container: Container<Key<'a>, JsonItem<'a>>) -> Self {
+        Children(unsafe { transmute::<Container<Key<'a>, JsonItem<'a>>, Container<Key<'static>, JsonItem<'static>>>(container) })
+    }
+
+    // Moves the children of nested containers to pending
+    fn detach(&mut self, pending: &mut Vec<Children>) {
+        match &mut self.0 {
+            Array(array) => {
+                pending.extend(array.iter_mut().filter_map(|item| item.payload.take_children()));
+            }
+            MapVec(map) | MapIndexed(map, _) => {
+                pending.extend(map.iter_mut().filter_map(|(_, item)| item.payload.take_children()));
+            }
+        }
+    }
+}
+
+impl Drop for Children {
+    // Nested containers are detached and dropped one at a time, so that deeply nested items do not overflow the stack
+    fn drop(&mut self) {
+        let mut pending = Vec::new();
+        self.detach(&mut pending);
+        while let Some(mut children) = pending.pop() {
+            children.detach(&mut pending);
+        }
+    }
+}
+
+impl Debug for Children {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.0, f)
+    }
+}
+
+impl<'a> JsonItem<'a> {
+    pub(crate) fn new(slice: Slice<'a>, json_type: JsonType) -> Self {
+        JsonItem { slice, json_type, payload: Payload::None }
     }
 
-    pub(crate) fn new_array(slice: Slice, array: Option<Vec<JsonItem>>) -> Self {
+    pub(crate) fn new_number(slice: Slice<'a>, layout: Option<NumberLayout>, decode: bool) -> Self {
+        match layout {
+            None => { Self::new(slice, JsonNumber) }
+            Some(layout) => {
+                let decoded = if decode { DecodedNumber::new(slice.as_str(), layout) } else { None };
+                let payload = match decoded {
+                    None => { Payload::Number(layout) }
+                    Some(DecodedNumber::Signed(value)) => { Payload::Signed(layout, value) }
+                    Some(DecodedNumber::Unsigned(value)) => { Payload::Unsigned(layout, value) }
+                    Some(DecodedNumber::Float(value)) => { Payload::Float(layout, value) }
+                };
+                JsonItem { slice, json_type: JsonNumber, payload }
+            }
+        }
+    }
+
+    pub(crate) fn new_array(slice: Slice<'a>, array: Option<Vec<JsonItem<'a>>>) -> Self {
         match array {
             None => { Self::new(slice, JsonArray) }
-            Some(array) => { JsonItem { slice, json_type: JsonArray, container: Some(Array(array)) } }
+            Some(array) => { JsonItem { slice, json_type: JsonArray, payload: Payload::Children(Children::new(Array(array))) } }
         }
     }
 
-    pub(crate) fn new_map(slice: Slice, map: Option<Vec<(Key, JsonItem)>>) -> Self {
+    // Duplicate keys are resolved here, the same way for both map containers: entries keep the position of the first
+    // occurrence of their key. On error, the first duplicated key in document order is returned.
+    pub(crate) fn new_map(slice: Slice<'a>, map: Option<Vec<(Key<'a>, JsonItem<'a>)>>, duplicates: DuplicateKeys) -> Result<Self, Key<'a>> {
         match map {
-            None => { Self::new(slice, JsonMap) }
+            None => { Ok(Self::new(slice, JsonMap)) }
             Some(map) => {
                 let container = if map.len() <= KEEP_VEC_THRESHOLD {
-                    MapVec(map)
+                    let mut vec: Vec<(Key, JsonItem)> = Vec::with_capacity(map.len());
+                    for (key, item) in map {
+                        match vec.iter_mut().find(|(k, _)| key.eq(k)) {
+                            None => { vec.push((key, item)); }
+                            Some((_, value)) => {
+                                match duplicates {
+                                    FirstWins => {}
+                                    LastWins => { *value = item; }
+                                    Error => { return Err(key); }
+                                }
+                            }
+                        }
+                    }
+                    MapVec(vec)
                 } else {
-                    MapBTree(map.into_iter().map(|(k, v)| (k, v)).collect())
+                    Self::indexed_map(map, duplicates)?
                 };
-                JsonItem { slice, json_type: JsonMap, container: Some(container) }
+                Ok(JsonItem { slice, json_type: JsonMap, payload: Payload::Children(Children::new(container)) })
             }
         }
     }
 
+    // Big objects keep their entries in document order, along with entry positions sorted by key
+    fn indexed_map(map: Vec<(Key<'a>, JsonItem<'a>)>, duplicates: DuplicateKeys) -> Result<Container<Key<'a>, JsonItem<'a>>, Key<'a>> {
+        // Sorting is stable, so that the occurrences of a key are in document order
+        let mut order: Vec<usize> = (0..map.len()).collect();
+        order.sort_by(|a, b| map[*a].0.cmp(&map[*b].0));
+
+        // Groups occurrences, pointing first occurrences to the occurrence providing their value
+        let mut source: Vec<Option<usize>> = (0..map.len()).map(Some).collect();
+        let mut first_duplicate = usize::MAX;
+        let mut i = 0;
+        while i < order.len() {
+            let first = order[i];
+            let mut j = i + 1;
+            while j < order.len() && map[order[j]].0 == map[first].0 {
+                source[order[j]] = None;
+                first_duplicate = first_duplicate.min(order[j]);
+                j += 1;
+            }
+            if j > i + 1 && duplicates == LastWins {
+                source[first] = Some(order[j - 1]);
+            }
+            i = j;
+        }
+        if duplicates == Error && first_duplicate != usize::MAX {
+            let mut map = map;
+            return Err(map.swap_remove(first_duplicate).0);
+        }
+
+        let mut map: Vec<Option<(Key<'a>, JsonItem<'a>)>> = map.into_iter().map(Some).collect();
+        let mut entries = Vec::with_capacity(map.len());
+        let mut positions = vec![0; map.len()];
+        for i in 0..map.len() {
+            if let (Some(from), Some((key, item))) = (source[i], map[i].take()) {
+                let item = if from == i { item } else { map[from].take().map_or(item, |(_, item)| item) };
+                positions[i] = entries.len();
+                entries.push((key, item));
+            }
+        }
+        let index = order.into_iter().filter(|i| source[*i].is_some()).map(|i| positions[i]).collect();
+        Ok(MapIndexed(entries, Box::new(index)))
+    }
+
     const fn empty() -> Self {
-        JsonItem { slice: Slice::empty(), json_type: Empty, container: None }
+        JsonItem { slice: Slice::empty(), json_type: Empty, payload: Payload::None }
+    }
+
+    fn container(&self) -> Option<&Container<Key<'a>, JsonItem<'a>>> {
+        match &self.payload {
+            Payload::Children(children) => { Some(&children.0) }
+            _ => { None }
+        }
+    }
+
+    // Memory range of the item in its source, quotes included for strings
+    fn bounds(&self) -> Range<usize> {
+        let start = self.slice.ptr as usize;
+        if self.json_type == JsonString {
+            start - 1..start + self.slice.len + 1
+        } else {
+            start..start + self.slice.len
+        }
+    }
+
+    // Child containing address, with its pointer token. Children are in document order, and object entries span from
+    // their key to the end of their value. Values kept from a later duplicate key are otherwise looked for one by one.
+    fn child_at(&self, address: usize) -> Option<(String, &JsonItem<'a>)> {
+        match self.container()? {
+            Array(array) => {
+                let index = array.partition_point(|item| item.bounds().start <= address).checked_sub(1)?;
+                array[index].bounds().contains(&address).then(|| (index.to_string(), &array[index]))
+            }
+            MapVec(map) | MapIndexed(map, _) => {
+                let index = map.partition_point(|(key, _)| key.slice.ptr as usize - 1 <= address);
+                let entry = index.checked_sub(1).map(|index| &map[index]).filter(|(_, item)| address < item.bounds().end);
+                let (key, item) = entry.or_else(|| map.iter().find(|(_, item)| item.bounds().contains(&address)))?;
+                Some((key.decoded().into_owned(), item))
+            }
+        }
+    }
+
+    fn number_layout(&self) -> Option<NumberLayout> {
+        match self.payload {
+            Payload::Number(layout) | Payload::Signed(layout, _) | Payload::Unsigned(layout, _) | Payload::Float(layout, _) => { Some(layout) }
+            _ => { None }
+        }
+    }
+
+    fn number_parts(&self) -> NumberParts<'_> {
+        match self.number_layout() {
+            Some(layout) => { NumberParts::with_layout(self.slice.as_str(), layout) }
+            None => { NumberParts::new(self.slice.as_str()) }
+        }
     }
 
     /// Returns &str value of item.
     /// This only returns `None` if the item is non-existent.
     /// In all other cases (even for `null`, `true`, `false`, numbers, arrays and objects), the text content of the item is returned, as extracted from the source data.
-    pub fn as_str(&self) -> Option<&str> {
+    /// The text is borrowed from the source data, so it outlives the item.
+    ///
+    /// ```rust
+    /// fn name(json: &str) -> Option<&str> {
+    ///     let parsed = jsonic::parse(json).ok()?;
+    ///     parsed["name"].as_str()
+    /// }
+    ///
+    /// assert_eq!(name("{\"name\": \"jsonic\"}"), Some("jsonic"));
+    /// ```
+    pub fn as_str(&self) -> Option<&'a str> {
         if self.json_type == Empty {
             None
         } else {
@@ -62,22 +275,199 @@ impl JsonItem {
         }
     }
 
+    /// If the item is a string, returns its decoded value, with escape sequences resolved.
+    /// The source text is borrowed when the string contains no escape sequence, so the value can outlive the item.
+    /// Lone surrogates are replaced with U+FFFD.
+    ///
+    /// ```rust
+    /// use std::borrow::Cow;
+    ///
+    /// let parsed = jsonic::parse("[\"caf\\u00e9\\n\"]").unwrap();
+    ///
+    /// assert_eq!(parsed[0].as_str(), Some("caf\\u00e9\\n"));
+    /// assert_eq!(parsed[0].as_string().as_deref(), Some("café\n"));
+    ///
+    /// fn strings(json: &str) -> Vec<Cow<'_, str>> {
+    ///     let parsed = jsonic::parse(json).unwrap();
+    ///     parsed.elements().map(|elements| elements.filter_map(|item| item.as_string()).collect()).unwrap_or_default()
+    /// }
+    ///
+    /// let decoded = strings("[\"a\", \"\\u0062\"]");
+    /// assert!(matches!(decoded.as_slice(), [Cow::Borrowed("a"), Cow::Owned(b)] if b == "b"));
+    /// ```
+    pub fn as_string(&self) -> Option<Cow<'a, str>> {
+        self.as_string_with(LoneSurrogates::Replace)
+    }
+
+    /// If the item is a string, returns its decoded value, using the provided policy for lone surrogates.
+    /// Returns `None` for other items, or if a lone surrogate is found and cannot be decoded as per the policy.
+    pub fn as_string_with(&self, lone_surrogates: LoneSurrogates) -> Option<Cow<'a, str>> {
+        if self.json_type != JsonString {
+            None
+        } else {
+            unescape_str(self.slice.as_str(), lone_surrogates)
+        }
+    }
+
+    /// If the item is a string, writes its decoded value to `writer`, without building it in memory first.
+    /// Lone surrogates are replaced with U+FFFD. Fails with [`io::ErrorKind::InvalidInput`] if the item is not a string.
+    pub fn write_unescaped(&self, writer: &mut impl Write) -> io::Result<()> {
+        self.write_unescaped_with(writer, LoneSurrogates::Replace)
+    }
+
+    /// If the item is a string, writes its decoded value to `writer`, using the provided policy for lone surrogates.
+    /// Lone surrogates found with [`LoneSurrogates::Error`] fail with [`io::ErrorKind::InvalidData`].
+    pub fn write_unescaped_with(&self, writer: &mut impl Write, lone_surrogates: LoneSurrogates) -> io::Result<()> {
+        if self.json_type != JsonString {
+            return Err(io::Error::new(InvalidInput, "item is not a string"));
+        }
+        unescape(self.slice.as_bytes(), lone_surrogates, |chunk| writer.write_all(chunk))
+    }
+
     /// Tries to convert item to `f64`. If the conversion fails, returns `None`.
     pub fn as_f64(&self) -> Option<f64> {
         if self.json_type != JsonNumber {
             None
         } else {
-            self.as_str()?.parse::<f64>().ok()
+            match self.payload {
+                Payload::Signed(_, value) => { Some(value as f64) }
+                Payload::Unsigned(_, value) => { Some(value as f64) }
+                Payload::Float(_, value) => { Some(value) }
+                _ => { to_f64(self.slice.as_str(), &self.number_parts()) }
+            }
+        }
+    }
+
+    /// Tries to convert item to `f64`, only succeeding if the resulting `f64` has exactly the value written in the source data.
+    /// Returns `None` if the number is rounded (like `0.1` or `9007199254740993`) or out of range (like `1e400`).
+    pub fn as_f64_exact(&self) -> Option<f64> {
+        let value = self.as_f64()?;
+        if is_exact(value, &self.number_parts()) {
+            Some(value)
+        } else {
+            None
         }
     }
 
     /// Tries to convert item to an `i128` integer. If the conversion fails, returns `None`.
     /// Resulting `i128` can then be converted to other integer types as required.
     pub fn as_i128(&self) -> Option<i128> {
+        self.parse_integer::<i128>()
+    }
+
+    /// Tries to convert item to an `i64` integer. Returns `None` if the item is not an integer, or if it overflows.
+    pub fn as_i64(&self) -> Option<i64> {
+        self.parse_integer::<i64>()
+    }
+
+    /// Tries to convert item to an `i32` integer. Returns `None` if the item is not an integer, or if it overflows.
+    pub fn as_i32(&self) -> Option<i32> {
+        self.parse_integer::<i32>()
+    }
+
+    /// Tries to convert item to a `u128` integer. Returns `None` if the item is not a positive integer, or if it overflows.
+    pub fn as_u128(&self) -> Option<u128> {
+        self.parse_integer::<u128>()
+    }
+
+    /// Tries to convert item to a `u64` integer. Returns `None` if the item is not a positive integer, or if it overflows.
+    pub fn as_u64(&self) -> Option<u64> {
+        self.parse_integer::<u64>()
+    }
+
+    /// Tries to convert item to a `u32` integer. Returns `None` if the item is not a positive integer, or if it overflows.
+    pub fn as_u32(&self) -> Option<u32> {
+        self.parse_integer::<u32>()
+    }
+
+    /// Tries to convert item to a `usize` integer. Returns `None` if the item is not a positive integer, or if it overflows.
+    pub fn as_usize(&self) -> Option<usize> {
+        self.parse_integer::<usize>()
+    }
+
+    fn parse_integer<T: FromStr + TryFrom<i128>>(&self) -> Option<T> {
+        if self.json_type != JsonNumber {
+            return None;
+        }
+        let raw = self.slice.as_str();
+        match self.payload {
+            Payload::Signed(_, value) => { T::try_from(value as i128).ok() }
+            Payload::Unsigned(_, value) => { T::try_from(value as i128).ok() }
+            Payload::Float(..) => { None }
+            Payload::Number(layout) => {
+                match parse_small_integer(raw, layout) {
+                    Some(value) => { T::try_from(value).ok() }
+                    None => { raw.parse::<T>().ok() }
+                }
+            }
+            _ => { raw.parse::<T>().ok() }
+        }
+    }
+
+    /// Tries to convert item to any integer type, also accepting numbers written with a fraction or exponent, as long as their value is integral.
+    /// Returns `None` if the number is not integral, or if it overflows.
+    ///
+    /// ```rust
+    /// let parsed = jsonic::parse("[2.0, 1e3, 2.5, 300]").unwrap();
+    ///
+    /// assert_eq!(parsed[0].as_i64(), None);
+    /// assert_eq!(parsed[0].as_integer::<i64>(), Some(2));
+    /// assert_eq!(parsed[1].as_integer::<u16>(), Some(1000));
+    /// assert_eq!(parsed[2].as_integer::<i64>(), None);
+    /// assert_eq!(parsed[3].as_integer::<u8>(), None);
+    /// ```
+    pub fn as_integer<T: TryFrom<i128>>(&self) -> Option<T> {
+        if self.json_type != JsonNumber {
+            None
+        } else {
+            T::try_from(self.number_parts().integer_value()?).ok()
+        }
+    }
+
+    /// If the item is a number, returns whether it was written as an integer or as a float, as recorded when parsing.
+    ///
+    /// ```rust
+    /// use jsonic::number::NumberKind;
+    ///
+    /// let parsed = jsonic::parse("[-12, 1.0, 1e3, \"1\"]").unwrap();
+    ///
+    /// assert_eq!(parsed[0].number_kind(), Some(NumberKind::Integer));
+    /// assert_eq!(parsed[1].number_kind(), Some(NumberKind::Float));
+    /// assert_eq!(parsed[2].number_kind(), Some(NumberKind::Float));
+    /// assert_eq!(parsed[3].number_kind(), None);
+    /// ```
+    pub fn number_kind(&self) -> Option<NumberKind> {
+        match self.number_layout() {
+            Some(layout) => { Some(layout.kind) }
+            None if self.json_type == JsonNumber => { Some(self.number_parts().kind(self.slice.as_str())) }
+            _ => { None }
+        }
+    }
+
+    /// Checks if the item is a number written without fraction nor exponent, in which case `as_i128` succeeds unless it overflows
+    pub fn is_integer(&self) -> bool {
+        self.number_kind() == Some(NumberKind::Integer)
+    }
+
+    /// Checks if the item is a number written with a fraction or an exponent
+    pub fn is_float(&self) -> bool {
+        self.number_kind() == Some(NumberKind::Float)
+    }
+
+    /// Checks if the item is a number written with a minus sign (`-0` included)
+    pub fn is_negative(&self) -> bool {
+        match self.number_layout() {
+            Some(layout) => { layout.negative }
+            None => { self.json_type == JsonNumber && self.number_parts().negative }
+        }
+    }
+
+    /// If the item is a number, returns a lossless view of it, giving access to its digits and exponent.
+    pub fn as_number(&self) -> Option<JsonNumberValue<'_>> {
         if self.json_type != JsonNumber {
             None
         } else {
-            self.as_str()?.parse::<i128>().ok()
+            Some(JsonNumberValue::new(self.slice.as_str(), self.number_parts()))
         }
     }
 
@@ -105,70 +495,291 @@ impl JsonItem {
         &self.json_type
     }
 
-    /// If the item is an array, returns an iterator over array elements. If the array is empty (`[]`), an empty iterator is returned.
-    /// Otherwise, returns `None`.
-    pub fn elements(&self) -> Option<ArrayIterator<JsonItem>> {
-        if let Some(container) = &self.container {
-            if let Array(array) = container {
-                return Some(ArrayIterator { iter: IterArrayVec(array.iter()) });
-            }
+    /// Returns the byte range of the item in `source`, the data it was parsed from, quotes included for strings.
+    /// Returns `None` if the item does not exist, or does not lie within `source`. See [`crate::line_index::LineIndex`]
+    /// to turn offsets into lines and columns.
+    ///
+    /// ```rust
+    /// let json = "{\"a\": [1, \"b\"]}";
+    /// let parsed = jsonic::parse(json).unwrap();
+    ///
+    /// assert_eq!(parsed["a"].span(json), Some(6..14));
+    /// assert_eq!(parsed["a"][1].span(json).map(|span| &json[span]), Some("\"b\""));
+    /// assert!(parsed["z"].span(json).is_none());
+    /// ```
+    pub fn span<S: AsRef<[u8]> + ?Sized>(&self, source: &S) -> Option<Range<usize>> {
+        if self.json_type == JsonString {
+            self.slice.quoted_span_in(source.as_ref())
         } else {
-            if self.json_type == JsonArray {
-                return Some(ArrayIterator { iter: IterArrayEmpty() });
+            let start = self.slice.offset_in(source.as_ref())?;
+            Some(start..start + self.slice.len)
+        }
+    }
+
+    /// Resolves a JSON Pointer (RFC 6901) like `/a/b/0` against the item. Returns `None` if the pointer is invalid,
+    /// or if it does not lead to an item. See [`crate::json_pointer::JsonPointer`] to parse a pointer once and evaluate it many times.
+    ///
+    /// ```rust
+    /// let parsed = jsonic::parse("{\"a\": {\"b/c\": [1, 2]}}").unwrap();
+    ///
+    /// assert_eq!(parsed.pointer("/a/b~1c/1").and_then(|item| item.as_i128()), Some(2));
+    /// assert!(parsed.pointer("").is_some_and(|item| item["a"].exists()));
+    /// assert!(parsed.pointer("/a/b~1c/2").is_none());
+    /// assert!(parsed.pointer("a").is_none());
+    /// ```
+    pub fn pointer(&self, pointer: &str) -> Option<&JsonItem<'a>> {
+        if pointer.is_empty() {
+            return Some(self);
+        }
+        let mut item = self;
+        for token in pointer.strip_prefix('/')?.split('/') {
+            item = resolve_token(item, &unescape_token(token).ok()?)?;
+        }
+        Some(item)
+    }
+
+    /// Returns the innermost item at a byte offset in `source`, the data the item was parsed from, along with its path.
+    /// Offsets from an object key to its value locate the value. Returns `None` if the offset is outside of the item.
+    ///
+    /// ```rust
+    /// let json = "{\"a\": [10, {\"b\": \"c\"}]}";
+    /// let parsed = jsonic::parse(json).unwrap();
+    ///
+    /// let (path, item) = parsed.locate(json, json.find('c').unwrap()).unwrap();
+    /// assert_eq!((path.to_string().as_str(), item.as_str()), ("/a/1/b", Some("c")));
+    ///
+    /// let (path, item) = parsed.locate(json, json.find('1').unwrap()).unwrap();
+    /// assert_eq!((path.to_string().as_str(), item.as_i128()), ("/a/0", Some(10)));
+    ///
+    /// let (path, _) = parsed.locate(json, json.len() - 1).unwrap();
+    /// assert_eq!(path.to_string(), "");
+    /// assert!(parsed.locate(json, json.len()).is_none());
+    /// ```
+    pub fn locate<S: AsRef<[u8]> + ?Sized>(&self, source: &S, offset: usize) -> Option<(JsonPointer, &JsonItem<'a>)> {
+        if !self.span(source)?.contains(&offset) {
+            return None;
+        }
+        let address = source.as_ref().as_ptr() as usize + offset;
+        let mut path = JsonPointer::root();
+        let mut item = self;
+        while let Some((token, child)) = item.child_at(address) {
+            path.push(token);
+            item = child;
+        }
+        Some((path, item))
+    }
+
+    /// Returns the path from the item to `target`, an item borrowed from its tree.
+    /// Returns `None` if `target` is not part of the tree.
+    ///
+    /// ```rust
+    /// let parsed = jsonic::parse("{\"a\": [10, {\"b/c\": null}]}").unwrap();
+    /// let target = &parsed["a"][1]["b/c"];
+    ///
+    /// assert_eq!(parsed.path_of(target).map(|path| path.to_string()), Some("/a/1/b~1c".to_owned()));
+    /// assert_eq!(parsed["a"].path_of(target).map(|path| path.to_string()), Some("/1/b~1c".to_owned()));
+    /// assert!(parsed["a"][0].path_of(target).is_none());
+    /// ```
+    pub fn path_of(&self, target: &JsonItem<'_>) -> Option<JsonPointer> {
+        if !target.exists() {
+            return None;
+        }
+        let address = target.slice.ptr as usize;
+        let mut path = JsonPointer::root();
+        let mut item = self;
+        while !addr_eq(item, target) {
+            let (token, child) = item.child_at(address)?;
+            path.push(token);
+            item = child;
+        }
+        Some(path)
+    }
+
+    /// Returns a depth-first iterator over the item and its descendants, yielding each with its path and depth.
+    /// Order, maximum depth and leaves-only walking are set on the returned [`Walker`], which can also skip subtrees.
+    ///
+    /// ```rust
+    /// let parsed = jsonic::parse("{\"a\": [1, 2], \"b\": {}}").unwrap();
+    ///
+    /// let paths: Vec<String> = parsed.walk().map(|(path, _, _)| path.to_dotted()).collect();
+    /// assert_eq!(paths, ["$", "$.a", "$.a[0]", "$.a[1]", "$.b"]);
+    /// ```
+    pub fn walk(&self) -> Walker<'_, 'a> {
+        Walker::new(self)
+    }
+
+    /// Returns an iterator over the item and its descendants matching predicate, depth-first in document order,
+    /// with their paths. See [`crate::find`] for common predicates.
+    ///
+    /// ```rust
+    /// let parsed = jsonic::parse("{\"a\": [\"short\", {\"b\": \"longer text\"}]}").unwrap();
+    ///
+    /// let long: Vec<String> = parsed.find_all(|item| item.as_string().is_some_and(|text| text.len() > 5))
+    ///     .map(|(path, _)| path.to_dotted())
+    ///     .collect();
+    /// assert_eq!(long, ["$.a[1].b"]);
+    /// ```
+    pub fn find_all<P: FnMut(&JsonItem<'a>) -> bool>(&self, predicate: P) -> Finder<'_, 'a, P> {
+        Finder::new(self, predicate)
+    }
+
+    /// Returns the first of the item and its descendants matching predicate, depth-first in document order,
+    /// with its path. See [`JsonItem::find_all`].
+    pub fn find_first<P: FnMut(&JsonItem<'a>) -> bool>(&self, predicate: P) -> Option<(NormalizedPath, &JsonItem<'a>)> {
+        self.find_all(predicate).next()
+    }
+
+    /// Drives a [`Visitor`] through the item and its descendants, depth-first in document order.
+    /// Returns `ControlFlow::Break(())` if the visitor stopped the traversal early.
+    ///
+    /// ```rust
+    /// use std::ops::ControlFlow;
+    /// use jsonic::visitor::Visitor;
+    ///
+    /// // Stops at the first negative number
+    /// struct Negative(Option<String>);
+    ///
+    /// impl Visitor for Negative {
+    ///     fn visit_number(&mut self, value: &str) -> ControlFlow<()> {
+    ///         if value.starts_with('-') {
+    ///             self.0 = Some(value.to_owned());
+    ///             return ControlFlow::Break(());
+    ///         }
+    ///         ControlFlow::Continue(())
+    ///     }
+    /// }
+    ///
+    /// let parsed = jsonic::parse("[1, [-2.5, -3]]").unwrap();
+    ///
+    /// let mut visitor = Negative(None);
+    /// assert!(parsed.accept(&mut visitor).is_break());
+    /// assert_eq!(visitor.0.as_deref(), Some("-2.5"));
+    /// ```
+    pub fn accept<V: Visitor + ?Sized>(&self, visitor: &mut V) -> ControlFlow<()> {
+        accept(self, visitor)
+    }
+
+    // Elements, if the item is an array
+    pub(crate) fn array(&self) -> Option<&[JsonItem<'a>]> {
+        match self.container() {
+            Some(Array(array)) => { Some(array) }
+            None if self.json_type == JsonArray => { Some(&[]) }
+            _ => { None }
+        }
+    }
+
+    /// Returns the element at index, if the item is an array and the index is in range.
+    /// Unlike indexing with `[]`, a missing element can be told apart from an existing one.
+    ///
+    /// ```rust
+    /// let parsed = jsonic::parse("{\"a\": [1, null]}").unwrap();
+    ///
+    /// assert!(parsed["a"].get_index(1).is_some_and(|item| item.is_null()));
+    /// assert!(parsed["a"].get_index(2).is_none());
+    /// assert!(parsed.get("b").is_none());
+    /// assert_eq!(parsed.get("a").map(|item| item.len()), Some(2));
+    /// ```
+    pub fn get_index(&self, index: usize) -> Option<&JsonItem<'a>> {
+        match self.container() {
+            Some(Array(array)) => { array.get(index) }
+            _ => { None }
+        }
+    }
+
+    /// Returns the value of key, if the item is an object containing it
+    pub fn get(&self, key: &str) -> Option<&JsonItem<'a>> {
+        self.get_key(&Key::from_str(key))
+    }
+
+    // Value of key, comparing keys losslessly, lone surrogates included
+    pub(crate) fn get_key(&self, key: &Key<'_>) -> Option<&JsonItem<'a>> {
+        match self.container()? {
+            MapVec(map) => { map.iter().find(|(k, _)| key.eq(k)).map(|(_, v)| v) }
+            MapIndexed(map, index) => {
+                let position = index.binary_search_by(|position| map[*position].0.cmp(key)).ok()?;
+                Some(&map[index[position]].1)
             }
+            _ => { None }
+        }
+    }
+
+    /// Checks if the item is an object containing key
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Returns the number of elements of an array, or the number of entries of an object. Other items have a length of 0.
+    pub fn len(&self) -> usize {
+        match self.container() {
+            Some(Array(array)) => { array.len() }
+            Some(MapVec(map) | MapIndexed(map, _)) => { map.len() }
+            None => { 0 }
+        }
+    }
+
+    /// Checks if the item has no elements or entries, see [`JsonItem::len`]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// If the item is an object, returns an iterator over its keys. Otherwise, returns `None`.
+    pub fn keys(&self) -> Option<KeyIterator<'_, Key<'a>, JsonItem<'a>>> {
+        self.entries().map(|iter| KeyIterator { iter })
+    }
+
+    /// If the item is an object, returns an iterator over its values. Otherwise, returns `None`.
+    pub fn values(&self) -> Option<ValueIterator<'_, Key<'a>, JsonItem<'a>>> {
+        self.entries().map(|iter| ValueIterator { iter })
+    }
+
+    /// If the item is an array, returns an iterator over array elements. If the array is empty (`[]`), an empty iterator is returned.
+    /// Otherwise, returns `None`.
+    pub fn elements(&self) -> Option<ArrayIterator<'_, JsonItem<'a>>> {
+        match self.container() {
+            Some(Array(array)) => { Some(ArrayIterator { iter: IterArrayVec(array.iter()) }) }
+            None if self.json_type == JsonArray => { Some(ArrayIterator { iter: IterArrayEmpty() }) }
+            _ => { None }
+        }
+    }
+
+    /// Returns the entry at position, in document order, if the item is an object and the position is in range
+    ///
+    /// ```rust
+    /// let parsed = jsonic::parse("{\"b\": 1, \"a\": 2}").unwrap();
+    ///
+    /// assert_eq!(parsed.entry_at(1).map(|(key, item)| (key.as_str(), item.as_i128())), Some(("a", Some(2))));
+    /// assert!(parsed.entry_at(2).is_none());
+    /// ```
+    pub fn entry_at(&self, position: usize) -> Option<(&Key<'a>, &JsonItem<'a>)> {
+        match self.container()? {
+            MapVec(map) | MapIndexed(map, _) => { map.get(position).map(|(key, item)| (key, item)) }
+            _ => { None }
         }
-        None
     }
 
-    /// If the item is an object, returns an iterator over object entries. If the object contains no entries (`{}`), an empty iterator is returned.
+    /// If the item is an object, returns an iterator over object entries, in document order. If the object contains no entries (`{}`), an empty iterator is returned.
     /// Otherwise, returns `None`.
-    pub fn entries(&self) -> Option<MapIterator<Key, JsonItem>> {
-        if let Some(container) = &self.container {
-            return match container {
-                MapVec(map) => { Some(MapIterator { iter: IterMapVec(map.iter()) }) }
-                MapBTree(map) => { Some(MapIterator { iter: IterMapBTree(map.iter()) }) }
-                _ => { None }
-            };
-        } else {
-            if self.json_type == JsonMap {
-                return Some(MapIterator { iter: IterMapEmpty() });
-            }
+    pub fn entries(&self) -> Option<MapIterator<'_, Key<'a>, JsonItem<'a>>> {
+        match self.container() {
+            Some(MapVec(map) | MapIndexed(map, _)) => { Some(MapIterator { iter: IterMapVec(map.iter()) }) }
+            None if self.json_type == JsonMap => { Some(MapIterator { iter: IterMapEmpty() }) }
+            _ => { None }
         }
-        None
     }
 }
 
-impl Index<usize> for JsonItem {
-    type Output = JsonItem;
+impl<'a> Index<usize> for JsonItem<'a> {
+    type Output = JsonItem<'a>;
 
     fn index(&self, index: usize) -> &Self::Output {
-        if let Some(container) = &self.container {
-            if let Array(array) = container {
-                return array.get(index).unwrap_or(&EMPTY_ITEM);
-            }
-        }
-        &EMPTY_ITEM
+        self.get_index(index).unwrap_or(&EMPTY_ITEM)
     }
 }
 
-impl Index<&str> for JsonItem {
-    type Output = JsonItem;
+impl<'a> Index<&str> for JsonItem<'a> {
+    type Output = JsonItem<'a>;
 
     fn index(&self, key: &str) -> &Self::Output {
-        let key = Key::from_str(key);
-        if let Some(container) = &self.container {
-            match container {
-                MapVec(map) => {
-                    for (k, v) in map {
-                        if key.eq(k) { return v; }
-                    }
-                }
-                MapBTree(map) => {
-                    return map.get(&key).unwrap_or(&EMPTY_ITEM);
-                }
-                _ => {}
-            }
-        }
-        &EMPTY_ITEM
+        self.get(key).unwrap_or(&EMPTY_ITEM)
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,212 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use crate::json_error::JsonError;
+use crate::json_error::JsonErrorKind::InvalidPointer;
+use crate::json_item::JsonItem;
+use crate::json_type::JsonType::{JsonArray, JsonMap};
+
+/// A JSON Pointer (RFC 6901), parsed once to be evaluated against any number of items.
+/// Pointers can also be built from path segments, and rendered back to their string form with `to_string()`.
+///
+/// ```rust
+/// use jsonic::json_pointer::JsonPointer;
+///
+/// let parsed = jsonic::parse("{\"users\": [{\"name\": \"Ann\"}, {\"name\": \"Bob\"}]}").unwrap();
+///
+/// let pointer = JsonPointer::new("/users/1/name").unwrap();
+/// assert_eq!(pointer.evaluate(&parsed).and_then(|item| item.as_str()), Some("Bob"));
+///
+/// let pointer = JsonPointer::root().join("users").join("0").join("name");
+/// assert_eq!(pointer.to_string(), "/users/0/name");
+/// assert_eq!(pointer.evaluate(&parsed).and_then(|item| item.as_str()), Some("Ann"));
+///
+/// assert_eq!(JsonPointer::from_tokens(["a/b", "c~d"]).to_string(), "/a~1b/c~0d");
+/// ```
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Default)]
+pub struct JsonPointer {
+    tokens: Vec<String>,
+}
+
+impl JsonPointer {
+    /// Parses a pointer. The empty string points to the root item, other pointers must start with `/`.
+    pub fn new(pointer: &str) -> Result<Self, JsonError> {
+        if pointer.is_empty() {
+            return Ok(Self::root());
+        }
+        if !pointer.starts_with('/') {
+            return Err(JsonError::with_kind(pointer.as_bytes(), 0, InvalidPointer));
+        }
+        let mut tokens = Vec::new();
+        let mut index = 1;
+        for token in pointer[1..].split('/') {
+            match unescape_token(token) {
+                Ok(token) => { tokens.push(token.into_owned()); }
+                Err(offset) => { return Err(JsonError::with_kind(pointer.as_bytes(), index + offset, InvalidPointer)); }
+            }
+            index += token.len() + 1;
+        }
+        Ok(JsonPointer { tokens })
+    }
+
+    /// Pointer to the root item
+    pub fn root() -> Self {
+        JsonPointer { tokens: Vec::new() }
+    }
+
+    /// Builds a pointer from unescaped path segments, object keys or array indexes
+    pub fn from_tokens<I: IntoIterator<Item = S>, S: Into<String>>(tokens: I) -> Self {
+        JsonPointer { tokens: tokens.into_iter().map(Into::into).collect() }
+    }
+
+    /// Appends a path segment to the pointer
+    pub fn push(&mut self, token: impl Into<String>) {
+        self.tokens.push(token.into());
+    }
+
+    /// Returns the pointer with a path segment appended
+    pub fn join(mut self, token: impl Into<String>) -> Self {
+        self.push(token);
+        self
+    }
+
+    /// Returns the unescaped path segments
+    pub fn tokens(&self) -> &[String] {
+        &self.tokens
+    }
+
+    /// Resolves the pointer against an item, returning `None` if it does not lead to an item
+    pub fn evaluate<'i, 'a>(&self, item: &'i JsonItem<'a>) -> Option<&'i JsonItem<'a>> {
+        self.tokens.iter().try_fold(item, |item, token| resolve_token(item, token))
+    }
+}
+
+impl Display for JsonPointer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for token in &self.tokens {
+            write!(f, "/{}", token.replace('~', "~0").replace('/', "~1"))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for JsonPointer {
+    type Err = JsonError;
+
+    fn from_str(pointer: &str) -> Result<Self, Self::Err> {
+        Self::new(pointer)
+    }
+}
+
+// Decodes `~1` to `/` and `~0` to `~`. On error, returns the offset of the invalid `~`.
+pub(crate) fn unescape_token(token: &str) -> Result<Cow<'_, str>, usize> {
+    if !token.contains('~') {
+        return Ok(Cow::Borrowed(token));
+    }
+    let mut unescaped = String::with_capacity(token.len());
+    let mut chars = token.char_indices();
+    while let Some((offset, c)) = chars.next() {
+        match c {
+            '~' => {
+                match chars.next() {
+                    Some((_, '0')) => { unescaped.push('~'); }
+                    Some((_, '1')) => { unescaped.push('/'); }
+                    _ => { return Err(offset); }
+                }
+            }
+            _ => { unescaped.push(c); }
+        }
+    }
+    Ok(Cow::Owned(unescaped))
+}
+
+// Steps into an object value or an array element. Array indexes must be written without leading zeros, and `-`
+// (the element after the last one) never resolves.
+pub(crate) fn resolve_token<'i, 'a>(item: &'i JsonItem<'a>, token: &str) -> Option<&'i JsonItem<'a>> {
+    match item.get_type() {
+        JsonMap => { item.get(token) }
+        JsonArray => {
+            let bytes = token.as_bytes();
+            if bytes.is_empty() || !bytes.iter().all(u8::is_ascii_digit) || (bytes[0] == b'0' && bytes.len() > 1) {
+                return None;
+            }
+            item.get_index(token.parse::<usize>().ok()?)
+        }
+        _ => { None }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants)]
+mod tests {
+    use crate::json_error::JsonErrorKind;
+    use crate::json_pointer::JsonPointer;
+    use crate::parse;
+
+    // Example document from RFC 6901, section 5
+    const RFC_JSON: &str = r#"{"foo": ["bar", "baz"], "": 0, "a/b": 1, "c%d": 2, "e^f": 3, "g|h": 4, "i\\j": 5, "k\"l": 6, " ": 7, "m~n": 8}"#;
+
+    #[test]
+    fn rfc_examples() {
+        match parse(RFC_JSON) {
+            Ok(parsed) => {
+                assert!(parsed.pointer("").is_some_and(|item| item["foo"].exists()));
+                assert_eq!(parsed.pointer("/foo").and_then(|item| item[1].as_str()), Some("baz"));
+                assert_eq!(parsed.pointer("/foo/0").and_then(|item| item.as_str()), Some("bar"));
+                let pointers = ["/", "/a~1b", "/c%d", "/e^f", "/g|h", "/i\\j", "/k\"l", "/ ", "/m~0n"];
+                for (value, pointer) in pointers.iter().enumerate() {
+                    assert_eq!(parsed.pointer(pointer).and_then(|item| item.as_i128()), Some(value as i128), "{}", pointer);
+                    let parsed_pointer = JsonPointer::new(pointer).unwrap();
+                    assert_eq!(parsed_pointer.evaluate(&parsed).and_then(|item| item.as_i128()), Some(value as i128), "{}", pointer);
+                    assert_eq!(parsed_pointer.to_string(), *pointer);
+                }
+            }
+            Err(error) => {
+                assert!(false, "{}", error.to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn unresolved() {
+        match parse(RFC_JSON) {
+            Ok(parsed) => {
+                for pointer in ["/foo/2", "/foo/-", "/foo/01", "/foo/+1", "/foo/0/0", "/bar", "/foo/", "a/b", "/m~2n"] {
+                    assert!(parsed.pointer(pointer).is_none(), "{}", pointer);
+                }
+            }
+            Err(error) => {
+                assert!(false, "{}", error.to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_pointers() {
+        for (pointer, index) in [("a", 0), ("/a~", 2), ("/a/b~2", 4), ("/~1/~x", 4)] {
+            match JsonPointer::new(pointer) {
+                Ok(_) => {
+                    assert!(false, "{}", pointer);
+                }
+                Err(error) => {
+                    assert_eq!(error.get_kind(), &JsonErrorKind::InvalidPointer);
+                    assert_eq!(error.get_index(), index, "{}", pointer);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn build() {
+        let mut pointer = JsonPointer::root();
+        assert_eq!(pointer.to_string(), "");
+        pointer.push("a");
+        pointer.push("1");
+        assert_eq!(pointer.to_string(), "/a/1");
+        assert_eq!(pointer.tokens(), ["a", "1"]);
+        assert_eq!("/a/1".parse::<JsonPointer>().unwrap(), pointer);
+        assert_eq!(JsonPointer::new("/~01").unwrap().tokens(), ["~1"]);
+    }
+}
@@ -0,0 +1,1301 @@
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::fmt::{Display, Formatter, Write};
+use std::iter::FusedIterator;
+use std::str::FromStr;
+
+use crate::iregexp::Regex;
+use crate::json_error::JsonError;
+use crate::json_error::JsonErrorKind::{DepthLimitExceeded, InvalidJsonPath};
+use crate::json_item::JsonItem;
+use crate::json_pointer::JsonPointer;
+use crate::json_type::JsonType::{JsonArray, JsonFalse, JsonMap, JsonNull, JsonNumber, JsonString, JsonTrue};
+use crate::number::{JsonNumberValue, NumberParts};
+
+// Indexes and slice bounds must be within the I-JSON range of exact integers
+const MAX_INTEGER: i64 = (1 << 53) - 1;
+// Filter expressions and function calls nesting limit, so that parsing and evaluating are not limited by the call stack
+const MAX_NESTING: usize = 64;
+
+/// A segment of a [`NormalizedPath`]
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+pub enum PathSegment {
+    /// Object member, with its decoded key
+    Key(String),
+    /// Array element
+    Index(usize),
+}
+
+/// Location of a node in a JSON value, rendered as a normalized path (RFC 9535, section 2.7) like `$['store']['book'][0]`
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Default)]
+pub struct NormalizedPath {
+    segments: Vec<PathSegment>,
+}
+
+impl NormalizedPath {
+    pub(crate) fn new(segments: Vec<PathSegment>) -> Self {
+        NormalizedPath { segments }
+    }
+
+    /// Returns the segments of the path, from the root
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    /// Converts the path to a JSON Pointer
+    pub fn to_pointer(&self) -> JsonPointer {
+        JsonPointer::from_tokens(self.segments.iter().map(|segment| {
+            match segment {
+                PathSegment::Key(key) => { key.clone() }
+                PathSegment::Index(index) => { index.to_string() }
+            }
+        }))
+    }
+
+    /// Renders the path with dot notation for keys that allow it, like `$.store.book[0]['first name']`
+    ///
+    /// ```rust
+    /// use jsonic::jsonpath::JsonPath;
+    ///
+    /// let parsed = jsonic::parse("{\"a\": [{\"b c\": 1, \"_d\": 2}]}").unwrap();
+    ///
+    /// let paths: Vec<String> = JsonPath::new("$.a[0].*").unwrap().query(&parsed).map(|(path, _)| path.to_dotted()).collect();
+    /// assert_eq!(paths, ["$.a[0]['b c']", "$.a[0]._d"]);
+    /// ```
+    pub fn to_dotted(&self) -> String {
+        let mut dotted = String::from("$");
+        for segment in &self.segments {
+            // Writing to a string cannot fail
+            let _ = match segment {
+                PathSegment::Key(key) if is_member_name(key) => { write!(dotted, ".{}", key) }
+                _ => { write_segment(&mut dotted, segment) }
+            };
+        }
+        dotted
+    }
+}
+
+// Keys allowed in member name shorthands (RFC 9535, section 2.5.1.1)
+fn is_member_name(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || !c.is_ascii())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || !c.is_ascii())
+}
+
+// Writes a segment in bracket notation
+fn write_segment(out: &mut impl Write, segment: &PathSegment) -> fmt::Result {
+    match segment {
+        PathSegment::Index(index) => { write!(out, "[{}]", index) }
+        PathSegment::Key(key) => {
+            out.write_str("['")?;
+            for c in key.chars() {
+                match c {
+                    '\'' => { out.write_str("\\'")?; }
+                    '\\' => { out.write_str("\\\\")?; }
+                    '\u{8}' => { out.write_str("\\b")?; }
+                    '\u{c}' => { out.write_str("\\f")?; }
+                    '\n' => { out.write_str("\\n")?; }
+                    '\r' => { out.write_str("\\r")?; }
+                    '\t' => { out.write_str("\\t")?; }
+                    '\0'..='\u{1f}' => { write!(out, "\\u{:04x}", c as u32)?; }
+                    _ => { out.write_char(c)?; }
+                }
+            }
+            out.write_str("']")
+        }
+    }
+}
+
+impl Display for NormalizedPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_char('$')?;
+        for segment in &self.segments {
+            write_segment(f, segment)?;
+        }
+        Ok(())
+    }
+}
+
+/// A compiled JSONPath query (RFC 9535), to be evaluated against any number of items.
+/// Filter expressions and the standard functions `length`, `count`, `match`, `search` and `value` are supported.
+/// Regular expressions follow I-Regexp (RFC 9485); general categories other than `L`, `Lu`, `Ll`, `N`, `Nd`, `Cc`, `Z`, `Zs`,
+/// `Zl` and `Zp` are not supported, and letter and number categories are approximated with the standard library's Unicode properties.
+///
+/// ```rust
+/// use jsonic::jsonpath::JsonPath;
+///
+/// let parsed = jsonic::parse(r#"{"store": {"book": [
+///     {"author": "Rees", "title": "Sayings", "price": 8.95},
+///     {"author": "Waugh", "title": "Sword", "price": 12.99}
+/// ]}}"#).unwrap();
+///
+/// let path = JsonPath::new("$.store.book[?@.price < 10].title").unwrap();
+/// let matches: Vec<_> = path.query(&parsed).map(|(path, item)| (path.to_string(), item.as_str())).collect();
+/// assert_eq!(matches, [("$['store']['book'][0]['title']".to_owned(), Some("Sayings"))]);
+///
+/// let path = JsonPath::new("$..author").unwrap();
+/// assert_eq!(path.query(&parsed).count(), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct JsonPath {
+    source: String,
+    query: Query,
+}
+
+impl JsonPath {
+    /// Compiles a query, which must start with `$`
+    pub fn new(path: &str) -> Result<Self, JsonError> {
+        let mut parser = Parser { source: path, index: 0, depth: 0 };
+        if !parser.eat('$') {
+            return Err(parser.error());
+        }
+        let query = parser.query()?;
+        if parser.index != path.len() {
+            return Err(parser.error());
+        }
+        Ok(JsonPath { source: path.to_owned(), query })
+    }
+
+    /// Evaluates the query against an item, returning the matched items with their normalized paths, in document order.
+    /// Matches are selected lazily, so stopping early saves evaluating the rest of the query.
+    pub fn query<'p, 'i, 'a>(&'p self, item: &'i JsonItem<'a>) -> JsonPathMatches<'p, 'i, 'a> {
+        JsonPathMatches { selection: Selection::new(&self.query, item, item, true) }
+    }
+}
+
+impl Display for JsonPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl FromStr for JsonPath {
+    type Err = JsonError;
+
+    fn from_str(path: &str) -> Result<Self, Self::Err> {
+        Self::new(path)
+    }
+}
+
+/// Iterator over the items matched by a [`JsonPath`], with their normalized paths
+pub struct JsonPathMatches<'p, 'i, 'a> {
+    selection: Selection<'p, 'i, 'a>,
+}
+
+impl<'i, 'a> Iterator for JsonPathMatches<'_, 'i, 'a> {
+    type Item = (NormalizedPath, &'i JsonItem<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.selection.next().map(|node| (NormalizedPath { segments: node.path }, node.item))
+    }
+}
+
+impl FusedIterator for JsonPathMatches<'_, '_, '_> {}
+
+#[derive(Debug, Clone)]
+struct Query {
+    segments: Vec<Segment>,
+}
+
+impl Query {
+    // Singular queries only contain child segments with a single name or index selector
+    fn is_singular(&self) -> bool {
+        self.segments.iter().all(|segment| {
+            !segment.descendant && segment.selectors.len() == 1 && matches!(segment.selectors[0], Selector::Name(_) | Selector::Index(_))
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Segment {
+    descendant: bool,
+    selectors: Vec<Selector>,
+}
+
+#[derive(Debug, Clone)]
+enum Selector {
+    Name(String),
+    Wildcard,
+    Index(i64),
+    Slice(Option<i64>, Option<i64>, Option<i64>),
+    Filter(Expression),
+}
+
+// Logical expressions of filter selectors
+#[derive(Debug, Clone)]
+enum Expression {
+    Or(Vec<Expression>),
+    And(Vec<Expression>),
+    Not(Box<Expression>),
+    // Absolute queries are evaluated against the root, relative ones against the current node
+    Exists(bool, Query),
+    Function(FunctionCall),
+    Comparison(Comparable, Comparator, Comparable),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Comparator {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+#[derive(Debug, Clone)]
+enum Comparable {
+    Literal(Literal),
+    Query(bool, Query),
+    Function(FunctionCall),
+}
+
+#[derive(Debug, Clone)]
+enum Literal {
+    Number(String),
+    String(String),
+    Bool(bool),
+    Null,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Type {
+    Value,
+    Logical,
+    Nodes,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Function {
+    Length,
+    Count,
+    Match,
+    Search,
+    Value,
+}
+
+impl Function {
+    fn new(name: &str) -> Option<Self> {
+        match name {
+            "length" => { Some(Function::Length) }
+            "count" => { Some(Function::Count) }
+            "match" => { Some(Function::Match) }
+            "search" => { Some(Function::Search) }
+            "value" => { Some(Function::Value) }
+            _ => { None }
+        }
+    }
+
+    fn parameters(self) -> &'static [Type] {
+        match self {
+            Function::Length => { &[Type::Value] }
+            Function::Count | Function::Value => { &[Type::Nodes] }
+            Function::Match | Function::Search => { &[Type::Value, Type::Value] }
+        }
+    }
+
+    fn result(self) -> Type {
+        match self {
+            Function::Length | Function::Count | Function::Value => { Type::Value }
+            Function::Match | Function::Search => { Type::Logical }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct FunctionCall {
+    function: Function,
+    arguments: Vec<Comparable>,
+    // Patterns given as literals are compiled once, `None` standing for an invalid pattern
+    pattern: Option<Option<Regex>>,
+}
+
+// A node, with its path when it is tracked
+struct Node<'i, 'a> {
+    item: &'i JsonItem<'a>,
+    path: Vec<PathSegment>,
+}
+
+impl<'i, 'a> Node<'i, 'a> {
+    fn child(&self, item: &'i JsonItem<'a>, segment: impl FnOnce() -> PathSegment, track: bool) -> Self {
+        let path = if track {
+            let mut path = self.path.clone();
+            path.push(segment());
+            path
+        } else {
+            Vec::new()
+        };
+        Node { item, path }
+    }
+
+    fn children(&self, track: bool) -> Vec<Self> {
+        if let Some(array) = self.item.array() {
+            array.iter().enumerate().map(|(i, item)| self.child(item, || PathSegment::Index(i), track)).collect()
+        } else if let Some(entries) = self.item.entries() {
+            entries.map(|(key, item)| self.child(item, || PathSegment::Key(key.decoded().into_owned()), track)).collect()
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+// Pending work of a selection, for the segment at the given index
+enum Task<'i, 'a> {
+    // Applies the segment to a node, or yields it past the last segment
+    Select(usize, Node<'i, 'a>),
+    // Applies the descendant segment to a node, then to its descendants
+    Descend(usize, Node<'i, 'a>),
+}
+
+// Nodes selected by a query, evaluated depth-first so that they come in document order without building the
+// intermediate node lists of each segment
+struct Selection<'q, 'i, 'a> {
+    segments: &'q [Segment],
+    root: &'i JsonItem<'a>,
+    track: bool,
+    pending: Vec<Task<'i, 'a>>,
+}
+
+impl<'q, 'i, 'a> Selection<'q, 'i, 'a> {
+    fn new(query: &'q Query, root: &'i JsonItem<'a>, start: &'i JsonItem<'a>, track: bool) -> Self {
+        let pending = vec![Task::Select(0, Node { item: start, path: Vec::new() })];
+        Selection { segments: &query.segments, root, track, pending }
+    }
+
+    // Stacks the nodes selected by the segment at index, so that they are popped in order
+    fn push_selected(&mut self, index: usize, node: &Node<'i, 'a>) {
+        let mut selected = Vec::new();
+        apply_selectors(&self.segments[index].selectors, node, self.root, self.track, &mut selected);
+        self.pending.extend(selected.into_iter().rev().map(|node| Task::Select(index + 1, node)));
+    }
+}
+
+impl<'i, 'a> Iterator for Selection<'_, 'i, 'a> {
+    type Item = Node<'i, 'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.pending.pop()? {
+                Task::Select(index, node) => {
+                    match self.segments.get(index) {
+                        None => { return Some(node); }
+                        Some(segment) if segment.descendant => { self.pending.push(Task::Descend(index, node)); }
+                        Some(_) => { self.push_selected(index, &node); }
+                    }
+                }
+                Task::Descend(index, node) => {
+                    // Descendants are visited in document order, each before its own descendants
+                    self.pending.extend(node.children(self.track).into_iter().rev().map(|child| Task::Descend(index, child)));
+                    self.push_selected(index, &node);
+                }
+            }
+        }
+    }
+}
+
+fn select<'q, 'i, 'a>(query: &'q Query, root: &'i JsonItem<'a>, current: &'i JsonItem<'a>, absolute: bool) -> Selection<'q, 'i, 'a> {
+    Selection::new(query, root, if absolute { root } else { current }, false)
+}
+
+fn apply_selectors<'i, 'a>(selectors: &[Selector], node: &Node<'i, 'a>, root: &'i JsonItem<'a>, track: bool, selected: &mut Vec<Node<'i, 'a>>) {
+    for selector in selectors {
+        match selector {
+            Selector::Name(name) => {
+                if let Some(item) = node.item.get(name) {
+                    selected.push(node.child(item, || PathSegment::Key(name.clone()), track));
+                }
+            }
+            Selector::Wildcard => { selected.extend(node.children(track)); }
+            Selector::Index(index) => {
+                if let Some(array) = node.item.array() {
+                    let index = if *index < 0 { array.len() as i64 + index } else { *index };
+                    if (0..array.len() as i64).contains(&index) {
+                        selected.push(node.child(&array[index as usize], || PathSegment::Index(index as usize), track));
+                    }
+                }
+            }
+            Selector::Slice(start, end, step) => {
+                if let Some(array) = node.item.array() {
+                    for index in slice_indexes(array.len() as i64, *start, *end, step.unwrap_or(1)) {
+                        selected.push(node.child(&array[index], || PathSegment::Index(index), track));
+                    }
+                }
+            }
+            Selector::Filter(expression) => {
+                for child in node.children(track) {
+                    if test(expression, root, child.item) {
+                        selected.push(child);
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Slice indexes, as per RFC 9535 section 2.3.4.2.2
+fn slice_indexes(length: i64, start: Option<i64>, end: Option<i64>, step: i64) -> Vec<usize> {
+    let normalize = |index: i64| { if index >= 0 { index } else { length + index } };
+    let mut indexes = Vec::new();
+    if step > 0 {
+        let lower = normalize(start.unwrap_or(0)).clamp(0, length);
+        let upper = normalize(end.unwrap_or(length)).clamp(0, length);
+        let mut i = lower;
+        while i < upper {
+            indexes.push(i as usize);
+            i += step;
+        }
+    } else if step < 0 {
+        let upper = normalize(start.unwrap_or(length - 1)).clamp(-1, length - 1);
+        let lower = normalize(end.unwrap_or(-length - 1)).clamp(-1, length - 1);
+        let mut i = upper;
+        while lower < i {
+            indexes.push(i as usize);
+            i += step;
+        }
+    }
+    indexes
+}
+
+fn test<'i, 'a>(expression: &Expression, root: &'i JsonItem<'a>, current: &'i JsonItem<'a>) -> bool {
+    match expression {
+        Expression::Or(expressions) => { expressions.iter().any(|expression| test(expression, root, current)) }
+        Expression::And(expressions) => { expressions.iter().all(|expression| test(expression, root, current)) }
+        Expression::Not(expression) => { !test(expression, root, current) }
+        Expression::Exists(absolute, query) => { select(query, root, current, *absolute).next().is_some() }
+        Expression::Function(call) => {
+            match call_function(call, root, current) {
+                Outcome::Logical(result) => { result }
+                Outcome::Value(_) => { false }
+            }
+        }
+        Expression::Comparison(left, comparator, right) => {
+            let left = evaluate(left, root, current);
+            let right = evaluate(right, root, current);
+            compare(&left.atom(), *comparator, &right.atom())
+        }
+    }
+}
+
+// Result of a comparable or of a function argument
+enum Value<'e, 'i, 'a> {
+    Nothing,
+    Item(&'i JsonItem<'a>),
+    Literal(&'e Literal),
+    Count(usize),
+}
+
+enum Outcome<'e, 'i, 'a> {
+    Value(Value<'e, 'i, 'a>),
+    Logical(bool),
+}
+
+fn evaluate<'e, 'i, 'a>(comparable: &'e Comparable, root: &'i JsonItem<'a>, current: &'i JsonItem<'a>) -> Value<'e, 'i, 'a> {
+    match comparable {
+        Comparable::Literal(literal) => { Value::Literal(literal) }
+        Comparable::Query(absolute, query) => {
+            match select(query, root, current, *absolute).next() {
+                Some(node) => { Value::Item(node.item) }
+                None => { Value::Nothing }
+            }
+        }
+        Comparable::Function(call) => {
+            match call_function(call, root, current) {
+                Outcome::Value(value) => { value }
+                _ => { Value::Nothing }
+            }
+        }
+    }
+}
+
+fn call_function<'e, 'i, 'a>(call: &'e FunctionCall, root: &'i JsonItem<'a>, current: &'i JsonItem<'a>) -> Outcome<'e, 'i, 'a> {
+    let nodes = |argument: &'e Comparable| {
+        match argument {
+            Comparable::Query(absolute, query) => { Some(select(query, root, current, *absolute)) }
+            _ => { None }
+        }.into_iter().flatten()
+    };
+    match call.function {
+        Function::Length => {
+            let value = evaluate(&call.arguments[0], root, current);
+            Outcome::Value(match value.atom() {
+                Atom::String(string) => { Value::Count(string.chars().count()) }
+                Atom::Array(array) => { Value::Count(array.len()) }
+                Atom::Object(object) => { Value::Count(object.len()) }
+                _ => { Value::Nothing }
+            })
+        }
+        Function::Count => { Outcome::Value(Value::Count(nodes(&call.arguments[0]).count())) }
+        Function::Value => {
+            // Only a single node has a value, so selecting stops at the second one
+            let mut nodes = nodes(&call.arguments[0]);
+            Outcome::Value(match (nodes.next(), nodes.next()) {
+                (Some(node), None) => { Value::Item(node.item) }
+                _ => { Value::Nothing }
+            })
+        }
+        Function::Match | Function::Search => {
+            let text = evaluate(&call.arguments[0], root, current);
+            let pattern = evaluate(&call.arguments[1], root, current);
+            let (text, pattern) = match (text.atom(), pattern.atom()) {
+                (Atom::String(text), Atom::String(pattern)) => { (text, pattern) }
+                _ => { return Outcome::Logical(false); }
+            };
+            let compiled;
+            let regex = match &call.pattern {
+                Some(regex) => { regex.as_ref() }
+                None => {
+                    compiled = Regex::new(&pattern);
+                    compiled.as_ref()
+                }
+            };
+            Outcome::Logical(match regex {
+                None => { false }
+                Some(regex) if call.function == Function::Match => { regex.is_match(&text) }
+                Some(regex) => { regex.is_found(&text) }
+            })
+        }
+    }
+}
+
+// Values as compared: numbers by their exact value, strings by their decoded value, arrays and objects deeply
+enum Atom<'v, 'a> {
+    Nothing,
+    Null,
+    Bool(bool),
+    Number(Cow<'v, str>),
+    String(Cow<'v, str>),
+    Array(&'v JsonItem<'a>),
+    Object(&'v JsonItem<'a>),
+}
+
+impl<'a> Value<'_, '_, 'a> {
+    fn atom(&self) -> Atom<'_, 'a> {
+        match self {
+            Value::Nothing => { Atom::Nothing }
+            Value::Item(item) => { item_atom(item) }
+            Value::Literal(Literal::Number(number)) => { Atom::Number(Cow::Borrowed(number)) }
+            Value::Literal(Literal::String(string)) => { Atom::String(Cow::Borrowed(string)) }
+            Value::Literal(Literal::Bool(value)) => { Atom::Bool(*value) }
+            Value::Literal(Literal::Null) => { Atom::Null }
+            Value::Count(count) => { Atom::Number(Cow::Owned(count.to_string())) }
+        }
+    }
+}
+
+fn item_atom<'v, 'a>(item: &'v JsonItem<'a>) -> Atom<'v, 'a> {
+    match item.get_type() {
+        JsonNull => { Atom::Null }
+        JsonTrue => { Atom::Bool(true) }
+        JsonFalse => { Atom::Bool(false) }
+        JsonNumber => { Atom::Number(Cow::Borrowed(item.as_number().map_or("0", |number| number.as_str()))) }
+        JsonString => { Atom::String(item.as_string().unwrap_or_default()) }
+        JsonArray => { Atom::Array(item) }
+        JsonMap => { Atom::Object(item) }
+        _ => { Atom::Nothing }
+    }
+}
+
+fn compare(left: &Atom<'_, '_>, comparator: Comparator, right: &Atom<'_, '_>) -> bool {
+    match comparator {
+        Comparator::Equal => { equal(left, right) }
+        Comparator::NotEqual => { !equal(left, right) }
+        Comparator::Less => { less(left, right) }
+        Comparator::LessOrEqual => { less(left, right) || equal(left, right) }
+        Comparator::Greater => { less(right, left) }
+        Comparator::GreaterOrEqual => { less(right, left) || equal(left, right) }
+    }
+}
+
+fn number_order(left: &str, right: &str) -> Ordering {
+    JsonNumberValue::new(left, NumberParts::new(left)).cmp(&JsonNumberValue::new(right, NumberParts::new(right)))
+}
+
+fn equal(left: &Atom<'_, '_>, right: &Atom<'_, '_>) -> bool {
+    match (left, right) {
+        (Atom::Array(left), Atom::Array(right)) | (Atom::Object(left), Atom::Object(right)) => { equal_items(left, right) }
+        _ => { equal_scalars(left, right) }
+    }
+}
+
+fn equal_scalars(left: &Atom<'_, '_>, right: &Atom<'_, '_>) -> bool {
+    match (left, right) {
+        (Atom::Nothing, Atom::Nothing) | (Atom::Null, Atom::Null) => { true }
+        (Atom::Bool(left), Atom::Bool(right)) => { left == right }
+        (Atom::Number(left), Atom::Number(right)) => { number_order(left, right) == Ordering::Equal }
+        (Atom::String(left), Atom::String(right)) => { left == right }
+        _ => { false }
+    }
+}
+
+// Deep equality, using an explicit stack so that nesting depth is not limited by the call stack
+fn equal_items<'l, 'r>(left: &JsonItem<'l>, right: &JsonItem<'r>) -> bool {
+    let mut pending: Vec<(&JsonItem<'l>, &JsonItem<'r>)> = vec![(left, right)];
+    while let Some((left, right)) = pending.pop() {
+        match (item_atom(left), item_atom(right)) {
+            (Atom::Array(left), Atom::Array(right)) => {
+                let (left, right) = (left.array().unwrap_or_default(), right.array().unwrap_or_default());
+                if left.len() != right.len() {
+                    return false;
+                }
+                pending.extend(left.iter().zip(right));
+            }
+            (Atom::Object(left), Atom::Object(right)) => {
+                if left.len() != right.len() {
+                    return false;
+                }
+                for (key, left) in left.entries().into_iter().flatten() {
+                    match right.get_key(key) {
+                        Some(right) => { pending.push((left, right)); }
+                        None => { return false; }
+                    }
+                }
+            }
+            (left, right) => {
+                if !equal_scalars(&left, &right) {
+                    return false;
+                }
+            }
+        }
+    }
+    true
+}
+
+fn less(left: &Atom<'_, '_>, right: &Atom<'_, '_>) -> bool {
+    match (left, right) {
+        (Atom::Number(left), Atom::Number(right)) => { number_order(left, right) == Ordering::Less }
+        // UTF-8 byte order is code point order
+        (Atom::String(left), Atom::String(right)) => { left < right }
+        _ => { false }
+    }
+}
+
+struct Parser<'s> {
+    source: &'s str,
+    index: usize,
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self) -> JsonError {
+        JsonError::with_kind(self.source.as_bytes(), self.index, InvalidJsonPath)
+    }
+
+    fn error_at(&self, index: usize) -> JsonError {
+        JsonError::with_kind(self.source.as_bytes(), index, InvalidJsonPath)
+    }
+
+    // Nested expressions and function calls are parsed recursively, so their depth is limited
+    fn nested<T>(&mut self, parse: impl FnOnce(&mut Self) -> Result<T, JsonError>) -> Result<T, JsonError> {
+        if self.depth == MAX_NESTING {
+            return Err(JsonError::with_kind(self.source.as_bytes(), self.index, DepthLimitExceeded));
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.source[self.index..].chars().next()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<u8> {
+        self.source.as_bytes().get(self.index + offset).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.index += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), JsonError> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.error())
+        }
+    }
+
+    fn skip_blanks(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek_at(0) {
+            self.index += 1;
+        }
+    }
+
+    // Segments following `$` or `@`
+    fn query(&mut self) -> Result<Query, JsonError> {
+        let mut segments = Vec::new();
+        loop {
+            let mark = self.index;
+            self.skip_blanks();
+            match self.peek() {
+                Some('.') if self.peek_at(1) == Some(b'.') => {
+                    self.index += 2;
+                    let selectors = match self.peek() {
+                        Some('[') => { self.bracketed_selection()? }
+                        Some('*') => {
+                            self.index += 1;
+                            vec![Selector::Wildcard]
+                        }
+                        _ => { vec![Selector::Name(self.member_name()?)] }
+                    };
+                    segments.push(Segment { descendant: true, selectors });
+                }
+                Some('.') => {
+                    self.index += 1;
+                    let selector = if self.eat('*') { Selector::Wildcard } else { Selector::Name(self.member_name()?) };
+                    segments.push(Segment { descendant: false, selectors: vec![selector] });
+                }
+                Some('[') => { segments.push(Segment { descendant: false, selectors: self.bracketed_selection()? }); }
+                _ => {
+                    self.index = mark;
+                    break;
+                }
+            }
+        }
+        Ok(Query { segments })
+    }
+
+    // member-name-shorthand = name-first *name-char
+    fn member_name(&mut self) -> Result<String, JsonError> {
+        let start = self.index;
+        while let Some(c) = self.peek() {
+            let allowed = c.is_ascii_alphabetic() || c == '_' || !c.is_ascii() || (c.is_ascii_digit() && self.index > start);
+            if !allowed {
+                break;
+            }
+            self.index += c.len_utf8();
+        }
+        if start == self.index {
+            return Err(self.error());
+        }
+        Ok(self.source[start..self.index].to_owned())
+    }
+
+    fn bracketed_selection(&mut self) -> Result<Vec<Selector>, JsonError> {
+        self.expect('[')?;
+        let mut selectors = Vec::new();
+        loop {
+            self.skip_blanks();
+            selectors.push(self.selector()?);
+            self.skip_blanks();
+            if self.eat(']') {
+                return Ok(selectors);
+            }
+            self.expect(',')?;
+        }
+    }
+
+    fn selector(&mut self) -> Result<Selector, JsonError> {
+        match self.peek() {
+            Some(quote @ ('\'' | '"')) => {
+                self.index += 1;
+                Ok(Selector::Name(self.string(quote)?))
+            }
+            Some('*') => {
+                self.index += 1;
+                Ok(Selector::Wildcard)
+            }
+            Some('?') => {
+                self.index += 1;
+                self.skip_blanks();
+                Ok(Selector::Filter(self.nested(Self::logical_or)?))
+            }
+            Some(':' | '-' | '0'..='9') => {
+                let start = self.integer()?;
+                self.skip_blanks();
+                if !self.eat(':') {
+                    return start.map(Selector::Index).ok_or_else(|| self.error());
+                }
+                self.skip_blanks();
+                let end = self.integer()?;
+                self.skip_blanks();
+                let step = if self.eat(':') {
+                    self.skip_blanks();
+                    self.integer()?
+                } else {
+                    None
+                };
+                Ok(Selector::Slice(start, end, step))
+            }
+            _ => { Err(self.error()) }
+        }
+    }
+
+    // Optional integer, without leading zeros nor `-0`, within the I-JSON range
+    fn integer(&mut self) -> Result<Option<i64>, JsonError> {
+        let start = self.index;
+        let negative = self.eat('-');
+        let digits = self.index;
+        while self.peek_at(0).is_some_and(|b| b.is_ascii_digit()) {
+            self.index += 1;
+        }
+        let text = &self.source[digits..self.index];
+        if text.is_empty() {
+            return if negative { Err(self.error()) } else { Ok(None) };
+        }
+        if (text.starts_with('0') && (text.len() > 1 || negative)) || text.len() > 16 {
+            return Err(self.error_at(start));
+        }
+        let value = text.parse::<i64>().map_err(|_| self.error_at(start))?;
+        if value > MAX_INTEGER {
+            return Err(self.error_at(start));
+        }
+        Ok(Some(if negative { -value } else { value }))
+    }
+
+    // String literal, after its opening quote
+    fn string(&mut self, quote: char) -> Result<String, JsonError> {
+        let mut string = String::new();
+        loop {
+            let c = self.peek().ok_or_else(|| self.error())?;
+            match c {
+                '\\' => {
+                    self.index += 1;
+                    let escape = self.peek().ok_or_else(|| self.error())?;
+                    self.index += escape.len_utf8();
+                    let escaped = match escape {
+                        'b' => { '\u{8}' }
+                        'f' => { '\u{c}' }
+                        'n' => { '\n' }
+                        'r' => { '\r' }
+                        't' => { '\t' }
+                        '/' => { '/' }
+                        '\\' => { '\\' }
+                        'u' => { self.unicode_escape()? }
+                        c if c == quote => { c }
+                        _ => { return Err(self.error_at(self.index - escape.len_utf8())); }
+                    };
+                    string.push(escaped);
+                }
+                '\0'..='\u{1f}' => { return Err(self.error()); }
+                _ => {
+                    self.index += c.len_utf8();
+                    if c == quote {
+                        return Ok(string);
+                    }
+                    string.push(c);
+                }
+            }
+        }
+    }
+
+    // Hexadecimal digits of a `\u` escape, and the low surrogate escape following a high surrogate
+    fn unicode_escape(&mut self) -> Result<char, JsonError> {
+        let high = self.hex4()?;
+        match high {
+            0xD800..=0xDBFF => {
+                if !(self.eat('\\') && self.eat('u')) {
+                    return Err(self.error());
+                }
+                let low = self.hex4()?;
+                if !(0xDC00..=0xDFFF).contains(&low) {
+                    return Err(self.error());
+                }
+                char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)).ok_or_else(|| self.error())
+            }
+            0xDC00..=0xDFFF => { Err(self.error()) }
+            _ => { char::from_u32(high).ok_or_else(|| self.error()) }
+        }
+    }
+
+    fn hex4(&mut self) -> Result<u32, JsonError> {
+        let digits = self.source.get(self.index..self.index + 4).filter(|digits| digits.bytes().all(|b| b.is_ascii_hexdigit()));
+        let value = digits.and_then(|digits| u32::from_str_radix(digits, 16).ok()).ok_or_else(|| self.error())?;
+        self.index += 4;
+        Ok(value)
+    }
+
+    fn logical_or(&mut self) -> Result<Expression, JsonError> {
+        let mut expressions = vec![self.logical_and()?];
+        loop {
+            let mark = self.index;
+            self.skip_blanks();
+            if !self.source[self.index..].starts_with("||") {
+                self.index = mark;
+                break;
+            }
+            self.index += 2;
+            self.skip_blanks();
+            expressions.push(self.logical_and()?);
+        }
+        Ok(if expressions.len() == 1 { expressions.remove(0) } else { Expression::Or(expressions) })
+    }
+
+    fn logical_and(&mut self) -> Result<Expression, JsonError> {
+        let mut expressions = vec![self.basic_expression()?];
+        loop {
+            let mark = self.index;
+            self.skip_blanks();
+            if !self.source[self.index..].starts_with("&&") {
+                self.index = mark;
+                break;
+            }
+            self.index += 2;
+            self.skip_blanks();
+            expressions.push(self.basic_expression()?);
+        }
+        Ok(if expressions.len() == 1 { expressions.remove(0) } else { Expression::And(expressions) })
+    }
+
+    // Parenthesized expression, comparison or test, possibly negated
+    fn basic_expression(&mut self) -> Result<Expression, JsonError> {
+        if self.eat('!') {
+            self.skip_blanks();
+            let expression = if self.peek() == Some('(') {
+                self.parenthesized()?
+            } else {
+                let start = self.index;
+                let comparable = self.comparable()?;
+                self.test_expression(comparable, start)?
+            };
+            return Ok(Expression::Not(Box::new(expression)));
+        }
+        if self.peek() == Some('(') {
+            return self.parenthesized();
+        }
+        let start = self.index;
+        let left = self.comparable()?;
+        let mark = self.index;
+        self.skip_blanks();
+        match self.comparator() {
+            Some(comparator) => {
+                self.check_comparable(&left, start)?;
+                self.skip_blanks();
+                let start = self.index;
+                let right = self.comparable()?;
+                self.check_comparable(&right, start)?;
+                Ok(Expression::Comparison(left, comparator, right))
+            }
+            None => {
+                self.index = mark;
+                self.test_expression(left, start)
+            }
+        }
+    }
+
+    fn parenthesized(&mut self) -> Result<Expression, JsonError> {
+        self.expect('(')?;
+        self.skip_blanks();
+        let expression = self.nested(Self::logical_or)?;
+        self.skip_blanks();
+        self.expect(')')?;
+        Ok(expression)
+    }
+
+    fn comparator(&mut self) -> Option<Comparator> {
+        let rest = &self.source[self.index..];
+        let (comparator, length) = if rest.starts_with("==") {
+            (Comparator::Equal, 2)
+        } else if rest.starts_with("!=") {
+            (Comparator::NotEqual, 2)
+        } else if rest.starts_with("<=") {
+            (Comparator::LessOrEqual, 2)
+        } else if rest.starts_with(">=") {
+            (Comparator::GreaterOrEqual, 2)
+        } else if rest.starts_with('<') {
+            (Comparator::Less, 1)
+        } else if rest.starts_with('>') {
+            (Comparator::Greater, 1)
+        } else {
+            return None;
+        };
+        self.index += length;
+        Some(comparator)
+    }
+
+    // Comparisons take literals, singular queries and functions returning values
+    fn check_comparable(&self, comparable: &Comparable, start: usize) -> Result<(), JsonError> {
+        match comparable {
+            Comparable::Query(_, query) if !query.is_singular() => { Err(self.error_at(start)) }
+            Comparable::Function(call) if call.function.result() != Type::Value => { Err(self.error_at(start)) }
+            _ => { Ok(()) }
+        }
+    }
+
+    // Tests take queries, and functions returning logical values
+    fn test_expression(&self, comparable: Comparable, start: usize) -> Result<Expression, JsonError> {
+        match comparable {
+            Comparable::Query(absolute, query) => { Ok(Expression::Exists(absolute, query)) }
+            Comparable::Function(call) if call.function.result() != Type::Value => { Ok(Expression::Function(call)) }
+            _ => { Err(self.error_at(start)) }
+        }
+    }
+
+    // Literal, query or function call
+    fn comparable(&mut self) -> Result<Comparable, JsonError> {
+        match self.peek() {
+            Some('@') => {
+                self.index += 1;
+                Ok(Comparable::Query(false, self.query()?))
+            }
+            Some('$') => {
+                self.index += 1;
+                Ok(Comparable::Query(true, self.query()?))
+            }
+            Some(quote @ ('\'' | '"')) => {
+                self.index += 1;
+                Ok(Comparable::Literal(Literal::String(self.string(quote)?)))
+            }
+            Some('-' | '0'..='9') => { Ok(Comparable::Literal(Literal::Number(self.number()?))) }
+            Some('a'..='z') => {
+                let start = self.index;
+                while let Some(b'a'..=b'z' | b'0'..=b'9' | b'_') = self.peek_at(0) {
+                    self.index += 1;
+                }
+                let name = &self.source[start..self.index];
+                if self.peek() == Some('(') {
+                    let function = Function::new(name).ok_or_else(|| self.error_at(start))?;
+                    return Ok(Comparable::Function(self.nested(|parser| parser.function_call(function, start))?));
+                }
+                match name {
+                    "true" => { Ok(Comparable::Literal(Literal::Bool(true))) }
+                    "false" => { Ok(Comparable::Literal(Literal::Bool(false))) }
+                    "null" => { Ok(Comparable::Literal(Literal::Null)) }
+                    _ => { Err(self.error_at(start)) }
+                }
+            }
+            _ => { Err(self.error()) }
+        }
+    }
+
+    // number = (int / "-0") [ frac ] [ exp ]
+    fn number(&mut self) -> Result<String, JsonError> {
+        let start = self.index;
+        let digits = |parser: &mut Self| {
+            let start = parser.index;
+            while parser.peek_at(0).is_some_and(|b| b.is_ascii_digit()) {
+                parser.index += 1;
+            }
+            parser.index - start
+        };
+        self.eat('-');
+        let integer = self.index;
+        match digits(self) {
+            0 => { return Err(self.error()); }
+            1 => {}
+            _ if self.source.as_bytes()[integer] == b'0' => { return Err(self.error_at(integer)); }
+            _ => {}
+        }
+        if self.eat('.') && digits(self) == 0 {
+            return Err(self.error());
+        }
+        if self.eat('e') || self.eat('E') {
+            let _ = self.eat('+') || self.eat('-');
+            if digits(self) == 0 {
+                return Err(self.error());
+            }
+        }
+        Ok(self.source[start..self.index].to_owned())
+    }
+
+    // Function arguments, after the function name, type checked against the function parameters
+    fn function_call(&mut self, function: Function, start: usize) -> Result<FunctionCall, JsonError> {
+        self.expect('(')?;
+        self.skip_blanks();
+        let mut arguments = Vec::new();
+        if !self.eat(')') {
+            loop {
+                let argument_start = self.index;
+                let argument = self.comparable()?;
+                let parameter = function.parameters().get(arguments.len()).ok_or_else(|| self.error_at(argument_start))?;
+                let valid = match (parameter, &argument) {
+                    (Type::Value, Comparable::Query(_, query)) => { query.is_singular() }
+                    (Type::Value, Comparable::Function(call)) => { call.function.result() == Type::Value }
+                    (Type::Value, Comparable::Literal(_)) => { true }
+                    (Type::Nodes, Comparable::Query(..)) => { true }
+                    _ => { false }
+                };
+                if !valid {
+                    return Err(self.error_at(argument_start));
+                }
+                arguments.push(argument);
+                self.skip_blanks();
+                if self.eat(')') {
+                    break;
+                }
+                self.expect(',')?;
+                self.skip_blanks();
+            }
+        }
+        if arguments.len() != function.parameters().len() {
+            return Err(self.error_at(start));
+        }
+        let pattern = match (function, arguments.get(1)) {
+            (Function::Match | Function::Search, Some(Comparable::Literal(Literal::String(pattern)))) => { Some(Regex::new(pattern)) }
+            _ => { None }
+        };
+        Ok(FunctionCall { function, arguments, pattern })
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants)]
+mod tests {
+    use crate::json_error::JsonErrorKind;
+    use crate::jsonpath::{JsonPath, NormalizedPath, PathSegment};
+    use crate::parse;
+
+    // Example documents from RFC 9535, sections 1.5 and 2.3.5.3
+    const STORE_JSON: &str = r#"{"store": {
+        "book": [
+            {"category": "reference", "author": "Nigel Rees", "title": "Sayings of the Century", "price": 8.95},
+            {"category": "fiction", "author": "Evelyn Waugh", "title": "Sword of Honour", "price": 12.99},
+            {"category": "fiction", "author": "Herman Melville", "title": "Moby Dick", "isbn": "0-553-21311-3", "price": 8.99},
+            {"category": "fiction", "author": "J. R. R. Tolkien", "title": "The Lord of the Rings", "isbn": "0-395-19395-8", "price": 22.99}
+        ],
+        "bicycle": {"color": "red", "price": 399}
+    }}"#;
+    const FILTER_JSON: &str = r#"{
+        "a": [3, 5, 1, 2, 4, 6, {"b": "j"}, {"b": "k"}, {"b": {}}, {"b": "kilo"}],
+        "o": {"p": 1, "q": 2, "r": 3, "s": 5, "t": {"u": 6}},
+        "e": "f"
+    }"#;
+
+    fn paths(json: &str, path: &str) -> Vec<String> {
+        match (parse(json), JsonPath::new(path)) {
+            (Ok(parsed), Ok(compiled)) => { compiled.query(&parsed).map(|(path, _)| path.to_string()).collect() }
+            (Err(error), _) | (_, Err(error)) => {
+                assert!(false, "{} {}", path, error);
+                Vec::new()
+            }
+        }
+    }
+
+    #[test]
+    fn rfc_examples() {
+        let authors = ["$['store']['book'][0]['author']", "$['store']['book'][1]['author']", "$['store']['book'][2]['author']", "$['store']['book'][3]['author']"];
+        assert_eq!(paths(STORE_JSON, "$.store.book[*].author"), authors);
+        assert_eq!(paths(STORE_JSON, "$..author"), authors);
+        assert_eq!(paths(STORE_JSON, "$.store.*"), ["$['store']['book']", "$['store']['bicycle']"]);
+        assert_eq!(paths(STORE_JSON, "$.store..price").len(), 5);
+        assert_eq!(paths(STORE_JSON, "$..book[2].author"), ["$['store']['book'][2]['author']"]);
+        assert!(paths(STORE_JSON, "$..book[2].publisher").is_empty());
+        assert_eq!(paths(STORE_JSON, "$..book[-1]"), ["$['store']['book'][3]"]);
+        assert_eq!(paths(STORE_JSON, "$..book[0,1]"), paths(STORE_JSON, "$..book[:2]"));
+        assert_eq!(paths(STORE_JSON, "$..book[?@.isbn]"), ["$['store']['book'][2]", "$['store']['book'][3]"]);
+        assert_eq!(paths(STORE_JSON, "$..book[?@.price<10]"), ["$['store']['book'][0]", "$['store']['book'][2]"]);
+        assert_eq!(paths(STORE_JSON, "$..*").len(), 27);
+        assert_eq!(paths(STORE_JSON, "$"), ["$"]);
+    }
+
+    #[test]
+    fn slices() {
+        let json = r#"["a", "b", "c", "d", "e", "f", "g"]"#;
+        assert_eq!(paths(json, "$[1:3]"), ["$[1]", "$[2]"]);
+        assert_eq!(paths(json, "$[5:]"), ["$[5]", "$[6]"]);
+        assert_eq!(paths(json, "$[1:5:2]"), ["$[1]", "$[3]"]);
+        assert_eq!(paths(json, "$[5:1:-2]"), ["$[5]", "$[3]"]);
+        assert_eq!(paths(json, "$[::-1]"), ["$[6]", "$[5]", "$[4]", "$[3]", "$[2]", "$[1]", "$[0]"]);
+        assert_eq!(paths(json, "$[-2:]"), ["$[5]", "$[6]"]);
+        assert!(paths(json, "$[::0]").is_empty());
+        assert_eq!(paths(json, "$[1:2:]"), ["$[1]"]);
+        assert!(paths(json, "$[7]").is_empty());
+        assert_eq!(paths(json, "$[ 1 : 2 ]"), ["$[1]"]);
+    }
+
+    #[test]
+    fn filters() {
+        assert_eq!(paths(FILTER_JSON, "$.a[?@.b == 'kilo']"), ["$['a'][9]"]);
+        assert_eq!(paths(FILTER_JSON, "$.a[?(@.b == 'kilo')]"), ["$['a'][9]"]);
+        assert_eq!(paths(FILTER_JSON, "$.a[?@>3.5]"), ["$['a'][1]", "$['a'][4]", "$['a'][5]"]);
+        assert_eq!(paths(FILTER_JSON, "$.a[?@.b]"), ["$['a'][6]", "$['a'][7]", "$['a'][8]", "$['a'][9]"]);
+        assert_eq!(paths(FILTER_JSON, "$[?@.*]"), ["$['a']", "$['o']"]);
+        assert_eq!(paths(FILTER_JSON, "$[?@[?@.b]]"), ["$['a']"]);
+        assert_eq!(paths(FILTER_JSON, "$.o[?@<3, ?@<3]"), ["$['o']['p']", "$['o']['q']", "$['o']['p']", "$['o']['q']"]);
+        assert_eq!(paths(FILTER_JSON, "$.a[?@<2 || @.b == \"k\"]"), ["$['a'][2]", "$['a'][7]"]);
+        assert_eq!(paths(FILTER_JSON, "$.a[?match(@.b, \"[jk]\")]"), ["$['a'][6]", "$['a'][7]"]);
+        assert_eq!(paths(FILTER_JSON, "$.a[?search(@.b, \"[jk]\")]"), ["$['a'][6]", "$['a'][7]", "$['a'][9]"]);
+        assert_eq!(paths(FILTER_JSON, "$.o[?@>1 && @<4]"), ["$['o']['q']", "$['o']['r']"]);
+        assert_eq!(paths(FILTER_JSON, "$.o[?@.u || @.x]"), ["$['o']['t']"]);
+        assert_eq!(paths(FILTER_JSON, "$.a[?@.b == $.x]").len(), 6);
+        assert_eq!(paths(FILTER_JSON, "$.a[?@ == @]").len(), 10);
+        assert_eq!(paths(FILTER_JSON, "$.a[?!@.b]").len(), 6);
+        assert_eq!(paths(FILTER_JSON, "$.a[?@ == 1.0e0]"), ["$['a'][2]"]);
+        assert_eq!(paths(FILTER_JSON, "$.o[?@ == $.o.t]"), ["$['o']['t']"]);
+        assert_eq!(paths(FILTER_JSON, "$.a[?@.b > 'j']"), ["$['a'][7]", "$['a'][9]"]);
+        assert_eq!(paths(r#"{"a": {"\ud800": 1}, "b": {"\udc00": 1}, "c": {"\ud800": 1}}"#, "$[?@ == $.a]"), ["$['a']", "$['c']"]);
+    }
+
+    #[test]
+    fn functions() {
+        let json = r#"{"a": [1, 2, 3], "b": "xé", "c": {"d": 1}, "e": "2024-01-15"}"#;
+        assert_eq!(paths(json, "$[?length(@) == 2]"), ["$['b']"]);
+        assert_eq!(paths(json, "$[?count(@.*) == 1]"), ["$['c']"]);
+        assert_eq!(paths(json, "$[?value(@..d) == 1]"), ["$['c']"]);
+        assert_eq!(paths(json, "$[?match(@, '\\\\d{4}-\\\\d{2}-\\\\d{2}')]"), Vec::<String>::new());
+        assert_eq!(paths(json, "$[?match(@, '[0-9]{4}-[0-9]{2}-[0-9]{2}')]"), ["$['e']"]);
+        assert_eq!(paths(json, "$[?match(@, $.b)]"), ["$['b']"]);
+        assert_eq!(paths(json, "$[?search(@, '(')]"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn invalid_paths() {
+        let invalid = [
+            ("", 0), (" $", 0), ("$ ", 1), ("$.", 2), ("$[", 2), ("$[01]", 2), ("$[-0]", 2), ("$.1", 2), ("$['a\\x']", 5),
+            ("$[9007199254740992]", 2), ("$[?@.a == @.*]", 10), ("$[?length(@.*) > 1]", 10), ("$[?count(1) == 1]", 9),
+            ("$[?match(@.a, 'x') == true]", 3), ("$[?length(@)]", 3), ("$[?true]", 3), ("$[?foo(@)]", 3), ("$[?@.a = 1]", 7),
+            ("$['\\ud800']", 9), ("$[1:2:3:]", 7), ("$.a..", 5), ("$[?value(@.a, @.b) == 1]", 14),
+        ];
+        for (path, index) in invalid {
+            match JsonPath::new(path) {
+                Ok(_) => {
+                    assert!(false, "{}", path);
+                }
+                Err(error) => {
+                    assert_eq!(error.get_kind(), &JsonErrorKind::InvalidJsonPath);
+                    assert_eq!(error.get_index(), index, "{}", path);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn deep_inputs() {
+        let depth = 200_000;
+        let deep = "[".repeat(depth) + &"]".repeat(depth);
+        let json = format!("{{\"a\": {}, \"b\": {}, \"c\": {}}}", deep, deep, "[".repeat(depth) + "1" + &"]".repeat(depth));
+        assert_eq!(paths(&json, "$[?@ == @]"), ["$['a']", "$['b']", "$['c']"]);
+        assert_eq!(paths(&json, "$[?@ == $.a]"), ["$['a']", "$['b']"]);
+        match (parse(&json), JsonPath::new("$..*")) {
+            (Ok(parsed), Ok(compiled)) => {
+                let first: Vec<String> = compiled.query(&parsed).take(5).map(|(path, _)| path.to_string()).collect();
+                assert_eq!(first, ["$['a']", "$['b']", "$['c']", "$['a'][0]", "$['a'][0][0]"]);
+            }
+            (Err(error), _) | (_, Err(error)) => {
+                assert!(false, "{}", error.to_string());
+            }
+        }
+
+        let nested = |open: &str, close: &str, count: usize| format!("$[?{}@{}]", open.repeat(count), close.repeat(count));
+        assert!(JsonPath::new(&nested("(", ")", 63)).is_ok());
+        assert!(JsonPath::new(&nested("count(@[?", "]) == 1", 31)).is_ok());
+        for path in [nested("(", ")", 64), nested("(", ")", depth), nested("@[?", "]", depth), nested("count(@[?", "]) == 1", 32), nested("length(", ")", depth)] {
+            match JsonPath::new(&path) {
+                Ok(_) => {
+                    assert!(false, "{}", &path[..10]);
+                }
+                Err(error) => {
+                    assert_eq!(error.get_kind(), &JsonErrorKind::DepthLimitExceeded);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn normalized_paths() {
+        let json = r#"{"a'b\\c\n": [{"A": true}]}"#;
+        assert_eq!(paths(json, "$..*"), ["$['a\\'b\\\\c\\n']", "$['a\\'b\\\\c\\n'][0]", "$['a\\'b\\\\c\\n'][0]['A']"]);
+        assert_eq!(paths(json, "$['a\\'b\\\\c\\n'][0].A"), ["$['a\\'b\\\\c\\n'][0]['A']"]);
+        let path = NormalizedPath { segments: vec![PathSegment::Key("a/b".to_owned()), PathSegment::Index(2)] };
+        assert_eq!(path.to_pointer().to_string(), "/a~1b/2");
+        assert_eq!(path.to_dotted(), "$['a/b'][2]");
+        let path = NormalizedPath::new(vec![PathSegment::Key("é_1".to_owned()), PathSegment::Key("1a".to_owned()), PathSegment::Key("a'".to_owned())]);
+        assert_eq!(path.to_dotted(), "$.é_1['1a']['a\\'']");
+    }
+}
@@ -1,8 +1,10 @@
+use std::borrow::Cow;
 use std::cmp::Ordering;
 use std::fmt::Debug;
-use std::ops::{Shl, Shr};
+use std::ops::{Range, Shl, Shr};
 
 use crate::slice::Slice;
+use crate::unescape::{unescape_str, LoneSurrogates, Wtf8Bytes};
 
 #[inline(always)]
 // Hash based on bytes at start/end, mid and array length
@@ -20,53 +22,106 @@ fn hash(bytes: &[u8]) -> u64 {
     hash
 }
 
-/// A struct representing JSON object keys
+/// A struct representing JSON object keys, borrowed from the source text.
+/// Keys are compared and hashed on their decoded value, so that `"caf\u00e9"` matches `"café"`. Lone surrogates are
+/// kept apart from each other and from U+FFFD, so that `"\ud800"`, `"\udc00"` and `"\ufffd"` are distinct keys.
 #[derive(Debug)]
-pub struct Key {
-    pub(crate) slice: Slice,
+pub struct Key<'a> {
+    pub(crate) slice: Slice<'a>,
     pub(crate) hash: u64,
+    escaped: bool,
 }
 
-impl Key {
-    pub(crate) fn from_str(source: &str) -> Self {
-        Self::from_slice(Slice::from_str(source))
+impl<'a> Key<'a> {
+    pub(crate) fn from_str(source: &'a str) -> Self {
+        Self::from_slice(Slice::from_str(source), false)
     }
 
-    pub(crate) fn from_slice(slice: Slice) -> Self {
-        let hash = hash(slice.as_bytes());
-        Key { slice, hash }
+    pub(crate) fn from_slice(slice: Slice<'a>, escaped: bool) -> Self {
+        let hash = if escaped {
+            // Decoded once, comparisons then decoding on the fly
+            hash(&Wtf8Bytes::new(slice.as_bytes(), true).collect::<Vec<u8>>())
+        } else {
+            hash(slice.as_bytes())
+        };
+        Key { slice, hash, escaped }
+    }
+
+    // Lossless decoded value, lone surrogates being encoded as per WTF-8, used to compare keys without allocating
+    fn comparable(&self) -> Wtf8Bytes<'a> {
+        Wtf8Bytes::new(self.slice.as_bytes(), self.escaped)
     }
 
-    /// Key text value
-    pub fn as_str(&self) -> &str {
+    /// Key text value, as found in the source data. The text is borrowed from the source data, so it outlives the key.
+    ///
+    /// ```rust
+    /// fn keys(json: &str) -> Vec<&str> {
+    ///     let parsed = jsonic::parse(json).unwrap();
+    ///     parsed.entries().map(|entries| entries.map(|(key, _)| key.as_str()).collect()).unwrap_or_default()
+    /// }
+    ///
+    /// assert_eq!(keys("{\"a\": 1, \"b\\n\": 2}"), ["a", "b\\n"]);
+    /// ```
+    pub fn as_str(&self) -> &'a str {
         self.slice.as_str()
     }
+
+    /// Returns the byte range of the key in `source`, the data it was parsed from, quotes included.
+    /// Returns `None` if the key does not lie within `source`.
+    pub fn span<S: AsRef<[u8]> + ?Sized>(&self, source: &S) -> Option<Range<usize>> {
+        self.slice.quoted_span_in(source.as_ref())
+    }
+
+    /// Key decoded value, with escape sequences resolved. Lone surrogates are replaced with U+FFFD.
+    /// The source text is borrowed when the key contains no escape sequence, so the value can outlive the key.
+    ///
+    /// ```rust
+    /// use std::borrow::Cow;
+    ///
+    /// fn keys(json: &str) -> Vec<Cow<'_, str>> {
+    ///     let parsed = jsonic::parse(json).unwrap();
+    ///     parsed.entries().map(|entries| entries.map(|(key, _)| key.decoded()).collect()).unwrap_or_default()
+    /// }
+    ///
+    /// assert_eq!(keys("{\"a\": 1, \"b\\n\": 2}"), ["a", "b\n"]);
+    /// ```
+    pub fn decoded(&self) -> Cow<'a, str> {
+        if self.escaped {
+            unescape_str(self.as_str(), LoneSurrogates::Replace).unwrap_or(Cow::Borrowed(self.as_str()))
+        } else {
+            Cow::Borrowed(self.as_str())
+        }
+    }
 }
 
-impl Eq for Key {}
+impl Eq for Key<'_> {}
 
-impl PartialEq<Self> for Key {
+impl PartialEq<Self> for Key<'_> {
     fn eq(&self, other: &Self) -> bool {
         if self.hash != other.hash {
             false
-        } else {
+        } else if !self.escaped && !other.escaped {
             self.as_str().eq(other.as_str())
+        } else {
+            self.comparable().eq(other.comparable())
         }
     }
 }
 
-impl PartialOrd<Self> for Key {
+impl PartialOrd<Self> for Key<'_> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
     }
 }
 
-impl Ord for Key {
+impl Ord for Key<'_> {
     fn cmp(&self, other: &Self) -> Ordering {
-        if self.hash == other.hash {
+        if self.hash != other.hash {
+            self.hash.cmp(&other.hash)
+        } else if !self.escaped && !other.escaped {
             self.as_str().cmp(other.as_str())
         } else {
-            self.hash.cmp(&other.hash)
+            self.comparable().cmp(other.comparable())
         }
     }
-}
\ No newline at end of file
+}
@@ -1,21 +1,41 @@
 use crate::json_error::JsonError;
+use crate::json_error::JsonErrorKind::{ArrayTooLong, ControlCharacter, DepthLimitExceeded, DuplicateKey, InputTooLarge, InvalidEscape, InvalidNumber, InvalidUtf8, ObjectTooLarge, StringTooLong, TooManyNodes, TrailingCharacters};
 use crate::json_item::JsonItem;
-use crate::json_type::JsonType::{JsonFalse, JsonNull, JsonNumber, JsonString, JsonTrue};
+use crate::json_type::JsonType::{JsonFalse, JsonNull, JsonString, JsonTrue};
 use crate::key::Key;
+use crate::number::NumberKind::{Float, Integer};
+use crate::number::NumberLayout;
+use crate::parse_options::NumberDecoding::Eager;
+use crate::parse_options::ParseOptions;
 use crate::slice::Slice;
 
 pub mod json_error;
 pub mod slice;
 pub mod json_item;
+pub mod json_document;
+pub mod json_pointer;
+pub mod jsonpath;
+pub mod line_index;
+pub mod walk;
+pub mod visitor;
+pub mod find;
+pub mod encoding;
+pub mod parse_options;
+pub mod unescape;
+pub mod number;
+mod power_of_five;
+mod iregexp;
 
 pub mod json_type;
 pub mod key;
 pub mod generics;
 
 const DEFAULT_VEC_CAPACITY: usize = 2;
+const UTF8_BOM: &[u8] = &[0xef, 0xbb, 0xbf];
+const DEFAULT_OPTIONS: ParseOptions = ParseOptions::new();
 
 #[inline(always)]
-fn shift_index(item: &JsonItem) -> usize {
+fn shift_index(item: &JsonItem<'_>) -> usize {
     if item.json_type == JsonString {
         item.slice.len + 2
     } else {
@@ -23,11 +43,36 @@ fn shift_index(item: &JsonItem) -> usize {
     }
 }
 
+// Validates the UTF-8 sequence starting at index, returns its length
 #[inline(always)]
-fn skip_spaces(bytes: &[u8], mut index: usize) -> Result<usize, JsonError> {
+fn check_utf8(bytes: &[u8], index: usize) -> Result<usize, JsonError> {
+    let continuation = |offset: usize, range: std::ops::RangeInclusive<u8>| {
+        bytes.get(index + offset).is_some_and(|b| range.contains(b))
+    };
+    let valid = match bytes[index] {
+        0x00..=0x7f => { return Ok(1); }
+        0xc2..=0xdf => { continuation(1, 0x80..=0xbf).then_some(2) }
+        0xe0 => { (continuation(1, 0xa0..=0xbf) && continuation(2, 0x80..=0xbf)).then_some(3) }
+        0xe1..=0xec | 0xee..=0xef => { (continuation(1, 0x80..=0xbf) && continuation(2, 0x80..=0xbf)).then_some(3) }
+        0xed => { (continuation(1, 0x80..=0x9f) && continuation(2, 0x80..=0xbf)).then_some(3) }
+        0xf0 => { (continuation(1, 0x90..=0xbf) && continuation(2, 0x80..=0xbf) && continuation(3, 0x80..=0xbf)).then_some(4) }
+        0xf1..=0xf3 => { (continuation(1, 0x80..=0xbf) && continuation(2, 0x80..=0xbf) && continuation(3, 0x80..=0xbf)).then_some(4) }
+        0xf4 => { (continuation(1, 0x80..=0x8f) && continuation(2, 0x80..=0xbf) && continuation(3, 0x80..=0xbf)).then_some(4) }
+        _ => { None }
+    };
+    valid.ok_or_else(|| JsonError::with_kind(bytes, index, InvalidUtf8))
+}
+
+#[inline(always)]
+fn skip_spaces<const VALIDATE_UTF8: bool>(bytes: &[u8], mut index: usize) -> Result<usize, JsonError> {
     while index < bytes.len() {
         match bytes[index] {
             b' ' | b'\n' | b'\r' | b'\t' => {}
+            0x80..=0xff if VALIDATE_UTF8 => {
+                // Never valid outside of strings, but invalid sequences are reported as such
+                check_utf8(bytes, index)?;
+                return Ok(index);
+            }
             _ => { return Ok(index); }
         }
         index += 1;
@@ -36,79 +81,164 @@ fn skip_spaces(bytes: &[u8], mut index: usize) -> Result<usize, JsonError> {
 }
 
 #[inline(always)]
-fn parse_null(bytes: &[u8], index: usize) -> Result<JsonItem, JsonError> {
-    if index + 3 < bytes.len() {
-        if bytes[index + 1] == b'u' && bytes[index + 2] == b'l' && bytes[index + 3] == b'l' {
-            return Ok(JsonItem::new(Slice::from_bytes(bytes, index, index + 4), JsonNull));
-        }
+fn parse_null(bytes: &[u8], index: usize) -> Result<JsonItem<'_>, JsonError> {
+    if index + 3 < bytes.len() && bytes[index + 1] == b'u' && bytes[index + 2] == b'l' && bytes[index + 3] == b'l' {
+        return Ok(JsonItem::new(Slice::from_bytes(bytes, index, index + 4), JsonNull));
     }
     Err(JsonError::new(bytes, index))
 }
 
 #[inline(always)]
-fn parse_true(bytes: &[u8], index: usize) -> Result<JsonItem, JsonError> {
-    if index + 3 < bytes.len() {
-        if bytes[index + 1] == b'r' && bytes[index + 2] == b'u' && bytes[index + 3] == b'e' {
-            return Ok(JsonItem::new(Slice::from_bytes(bytes, index, index + 4), JsonTrue));
-        }
+fn parse_true(bytes: &[u8], index: usize) -> Result<JsonItem<'_>, JsonError> {
+    if index + 3 < bytes.len() && bytes[index + 1] == b'r' && bytes[index + 2] == b'u' && bytes[index + 3] == b'e' {
+        return Ok(JsonItem::new(Slice::from_bytes(bytes, index, index + 4), JsonTrue));
     }
     Err(JsonError::new(bytes, index))
 }
 
 #[inline(always)]
-fn parse_false(bytes: &[u8], index: usize) -> Result<JsonItem, JsonError> {
-    if index + 4 < bytes.len() {
-        if bytes[index + 1] == b'a' && bytes[index + 2] == b'l' && bytes[index + 3] == b's' && bytes[index + 4] == b'e' {
-            return Ok(JsonItem::new(Slice::from_bytes(bytes, index, index + 5), JsonFalse));
-        }
+fn parse_false(bytes: &[u8], index: usize) -> Result<JsonItem<'_>, JsonError> {
+    if index + 4 < bytes.len() && bytes[index + 1] == b'a' && bytes[index + 2] == b'l' && bytes[index + 3] == b's' && bytes[index + 4] == b'e' {
+        return Ok(JsonItem::new(Slice::from_bytes(bytes, index, index + 5), JsonFalse));
     }
     Err(JsonError::new(bytes, index))
 }
 
 #[inline(always)]
-fn parse_number(bytes: &[u8], mut index: usize) -> Result<JsonItem, JsonError> {
+fn skip_digits(bytes: &[u8], mut index: usize) -> usize {
+    while index < bytes.len() && bytes[index].is_ascii_digit() {
+        index += 1;
+    }
+    index
+}
+
+// Validates number grammar: -?(0|[1-9][0-9]*)(\.[0-9]+)?([eE][+-]?[0-9]+)?
+#[inline(always)]
+fn parse_number<'a>(bytes: &'a [u8], mut index: usize, options: &ParseOptions) -> Result<JsonItem<'a>, JsonError> {
+    let invalid = |index: usize| { JsonError::with_kind(bytes, index, InvalidNumber) };
     let mark = index;
-    index += 1;
-    while index < bytes.len() {
-        match bytes[index] {
-            b'0'..=b'9' | b'+' | b'-' | b'.' | b'e' | b'E' => {}
-            _ => {
-                return Ok(JsonItem::new(Slice::from_bytes(bytes, mark, index), JsonNumber));
-            }
+    let negative = bytes[index] == b'-';
+
+    // Sign and integer part
+    if negative {
+        index += 1;
+    }
+    match bytes.get(index) {
+        Some(b'0') => { index += 1; }
+        Some(b'1'..=b'9') => { index = skip_digits(bytes, index + 1); }
+        _ => { return Err(invalid(index)); }
+    }
+    let integer_end = index;
+
+    // Fraction
+    if bytes.get(index) == Some(&b'.') {
+        index += 1;
+        if !bytes.get(index).is_some_and(u8::is_ascii_digit) {
+            return Err(invalid(index));
         }
+        index = skip_digits(bytes, index);
+    }
+    let fraction_end = index;
+    let mut kind = if fraction_end > integer_end { Float } else { Integer };
+
+    // Exponent
+    if let Some(b'e' | b'E') = bytes.get(index) {
+        kind = Float;
         index += 1;
+        if let Some(b'+' | b'-') = bytes.get(index) {
+            index += 1;
+        }
+        if !bytes.get(index).is_some_and(u8::is_ascii_digit) {
+            return Err(invalid(index));
+        }
+        index = skip_digits(bytes, index);
     }
-    Err(JsonError::new(bytes, index))
+
+    // A number-like character right after the number means it was malformed, as in 01 or 1-2
+    if let Some(b'0'..=b'9' | b'+' | b'-' | b'.' | b'e' | b'E') = bytes.get(index) {
+        return Err(invalid(index));
+    }
+
+    // Digit boundaries are kept so that decoding the number does not scan it again
+    let layout = match (u32::try_from(integer_end - mark), u32::try_from(fraction_end - mark)) {
+        (Ok(integer_end), Ok(fraction_end)) => { Some(NumberLayout { kind, negative, integer_end, fraction_end }) }
+        _ => { None }
+    };
+    Ok(JsonItem::new_number(Slice::from_bytes(bytes, mark, index), layout, options.number_decoding == Eager))
 }
 
+// Validates the escape sequence starting at index, returns the index following it
 #[inline(always)]
-fn parse_string(bytes: &[u8], mut index: usize) -> Result<JsonItem, JsonError> {
+fn check_escape(bytes: &[u8], index: usize) -> Result<usize, JsonError> {
+    match bytes.get(index + 1) {
+        Some(b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't') => { Ok(index + 2) }
+        Some(b'u') => {
+            for i in index + 2..index + 6 {
+                match bytes.get(i) {
+                    Some(b) if b.is_ascii_hexdigit() => {}
+                    Some(_) => { return Err(JsonError::with_kind(bytes, i, InvalidEscape)); }
+                    None => { return Err(JsonError::new(bytes, i)); }
+                }
+            }
+            Ok(index + 6)
+        }
+        Some(_) => { Err(JsonError::with_kind(bytes, index + 1, InvalidEscape)) }
+        None => { Err(JsonError::new(bytes, index + 1)) }
+    }
+}
+
+// Scans string content, returns it along with whether it contains escape sequences
+#[inline(always)]
+fn scan_string<'a, const VALIDATE_UTF8: bool>(bytes: &'a [u8], mut index: usize, options: &ParseOptions) -> Result<(Slice<'a>, bool), JsonError> {
     index += 1;
     let mark = index;
-    let mut b = 0;
+    let mut escaped = false;
     while index < bytes.len() {
-        let p = b;
-        b = bytes[index];
-        if b == b'"' {
-            if p != b'\\' {
-                return Ok(JsonItem::new(Slice::from_bytes(bytes, mark, index), JsonString));
+        match bytes[index] {
+            b'"' => {
+                if index - mark > options.max_string_length {
+                    return Err(JsonError::with_kind(bytes, mark - 1, StringTooLong));
+                }
+                return Ok((Slice::from_bytes(bytes, mark, index), escaped));
             }
+            b'\\' => {
+                index = check_escape(bytes, index)?;
+                escaped = true;
+            }
+            0x00..=0x1f => { return Err(JsonError::with_kind(bytes, index, ControlCharacter)); }
+            0x80..=0xff if VALIDATE_UTF8 => { index += check_utf8(bytes, index)?; }
+            _ => { index += 1; }
         }
-        index += 1;
     }
     Err(JsonError::new(bytes, index))
 }
 
 #[inline(always)]
-fn parse_item(bytes: &[u8], index: usize) -> Result<JsonItem, JsonError> {
+fn parse_string<'a, const VALIDATE_UTF8: bool>(bytes: &'a [u8], index: usize, options: &ParseOptions) -> Result<JsonItem<'a>, JsonError> {
+    let (slice, _) = scan_string::<VALIDATE_UTF8>(bytes, index, options)?;
+    Ok(JsonItem::new(slice, JsonString))
+}
+
+// Object or array being parsed, kept on an explicit stack so that nesting depth is not limited by the call stack
+enum Frame<'a> {
+    Map { mark: usize, map: Option<Vec<(Key<'a>, JsonItem<'a>)>>, key: Option<Key<'a>> },
+    Array { mark: usize, array: Option<Vec<JsonItem<'a>>> },
+}
+
+// Parsing progress: either a value starts at index, or an item was completed and parsing resumes at index
+enum Step<'a> {
+    Value(usize),
+    Item(JsonItem<'a>, usize),
+}
+
+#[inline(always)]
+fn parse_scalar<'a, const VALIDATE_UTF8: bool>(bytes: &'a [u8], index: usize, options: &ParseOptions) -> Result<JsonItem<'a>, JsonError> {
     match bytes[index] {
         b'n' => { Ok(parse_null(bytes, index)?) }
         b't' => { Ok(parse_true(bytes, index)?) }
         b'f' => { Ok(parse_false(bytes, index)?) }
-        b'+' | b'-' | b'0'..=b'9' => { Ok(parse_number(bytes, index)?) }
-        b'"' => { Ok(parse_string(bytes, index)?) }
-        b'{' => { Ok(parse_map(bytes, index)?) }
-        b'[' => { Ok(parse_array(bytes, index)?) }
+        b'+' | b'-' | b'0'..=b'9' => { Ok(parse_number(bytes, index, options)?) }
+        b'"' => { Ok(parse_string::<VALIDATE_UTF8>(bytes, index, options)?) }
         _ => {
             Err(JsonError::new(bytes, index))
         }
@@ -116,96 +246,145 @@ fn parse_item(bytes: &[u8], index: usize) -> Result<JsonItem, JsonError> {
 }
 
 #[inline(always)]
-fn parse_map(bytes: &[u8], mut index: usize) -> Result<JsonItem, JsonError> {
-    let mark = index;
-    index += 1;
-    let mut map = None;
+fn parse_item<'a, const VALIDATE_UTF8: bool>(bytes: &'a [u8], index: usize, options: &ParseOptions) -> Result<JsonItem<'a>, JsonError> {
+    let mut stack: Vec<Frame<'a>> = Vec::new();
+    let mut nodes = 0_usize;
+    let mut step = Step::Value(index);
     loop {
-        // Spaces
-        index = skip_spaces(bytes, index)?;
-
-        // Check ending
-        match bytes[index] {
-            b'}' => {
-                return Ok(JsonItem::new_map(Slice::from_bytes(bytes, mark, index + 1), map));
-            }
-            b',' => {
-                index = skip_spaces(bytes, index + 1)?;
+        step = match step {
+            Step::Value(index) => {
+                nodes += 1;
+                if nodes > options.max_nodes {
+                    return Err(JsonError::with_kind(bytes, index, TooManyNodes));
+                }
+                if (bytes[index] == b'{' || bytes[index] == b'[') && stack.len() >= options.max_depth {
+                    return Err(JsonError::with_kind(bytes, index, DepthLimitExceeded));
+                }
+                match bytes[index] {
+                    b'{' => {
+                        let (mut map, mut key) = (None, None);
+                        let step = parse_map::<VALIDATE_UTF8>(bytes, index + 1, index, &mut map, &mut key, options)?;
+                        if let Step::Value(_) = step {
+                            stack.push(Frame::Map { mark: index, map, key });
+                        }
+                        step
+                    }
+                    b'[' => {
+                        let mut array = None;
+                        let step = parse_array::<VALIDATE_UTF8>(bytes, index + 1, index, &mut array, options)?;
+                        if let Step::Value(_) = step {
+                            stack.push(Frame::Array { mark: index, array });
+                        }
+                        step
+                    }
+                    _ => {
+                        let item = parse_scalar::<VALIDATE_UTF8>(bytes, index, options)?;
+                        let index = index + shift_index(&item);
+                        Step::Item(item, index)
+                    }
+                }
             }
-            _ => {
-                if !map.is_none() {
-                    return Err(JsonError::new(bytes, index));
+            Step::Item(item, index) => {
+                // Store
+                let step = match stack.last_mut() {
+                    None => { return Ok(item); }
+                    Some(Frame::Map { mark, map, key }) => {
+                        if let Some(key) = key.take() {
+                            map.get_or_insert_with(|| Vec::with_capacity(DEFAULT_VEC_CAPACITY)).push((key, item));
+                        }
+                        parse_map::<VALIDATE_UTF8>(bytes, index, *mark, map, key, options)?
+                    }
+                    Some(Frame::Array { mark, array }) => {
+                        array.get_or_insert_with(|| Vec::with_capacity(DEFAULT_VEC_CAPACITY)).push(item);
+                        parse_array::<VALIDATE_UTF8>(bytes, index, *mark, array, options)?
+                    }
+                };
+                if let Step::Item(..) = step {
+                    stack.pop();
                 }
+                step
             }
-        }
+        };
+    }
+}
 
-        // Key
-        let key = parse_string(bytes, index)?;
-        index += shift_index(&key);
+// Continues parsing an object, up to its next value or its end
+#[inline(always)]
+fn parse_map<'a, const VALIDATE_UTF8: bool>(bytes: &'a [u8], mut index: usize, mark: usize, map: &mut Option<Vec<(Key<'a>, JsonItem<'a>)>>, key: &mut Option<Key<'a>>, options: &ParseOptions) -> Result<Step<'a>, JsonError> {
+    // Spaces
+    index = skip_spaces::<VALIDATE_UTF8>(bytes, index)?;
 
-        // Separator
-        index = skip_spaces(bytes, index)?;
-        if bytes[index] != b':' {
-            return Err(JsonError::new(bytes, index));
-        } else {
-            index = skip_spaces(bytes, index + 1)?;
+    // Check ending
+    match bytes[index] {
+        b'}' => {
+            let item = JsonItem::new_map(Slice::from_bytes(bytes, mark, index + 1), map.take(), options.duplicate_keys).map_err(|key| {
+                let index = key.slice.ptr as usize - bytes.as_ptr() as usize - 1;
+                JsonError::with_kind(bytes, index, DuplicateKey(key.decoded().into_owned()))
+            })?;
+            return Ok(Step::Item(item, index + 1));
+        }
+        b',' if map.is_some() => {
+            index = skip_spaces::<VALIDATE_UTF8>(bytes, index + 1)?;
+        }
+        _ => {
+            if map.is_some() {
+                return Err(JsonError::new(bytes, index));
+            }
         }
+    }
 
-        // Value
-        let item = parse_item(bytes, index)?;
-        index += shift_index(&item);
+    // Key
+    if bytes[index] != b'"' {
+        return Err(JsonError::new(bytes, index));
+    }
+    if map.as_ref().map_or(0, Vec::len) >= options.max_object_entries {
+        return Err(JsonError::with_kind(bytes, index, ObjectTooLarge));
+    }
+    let (slice, escaped) = scan_string::<VALIDATE_UTF8>(bytes, index, options)?;
+    index += slice.len + 2;
+    *key = Some(Key::from_slice(slice, escaped));
 
-        // Store
-        if let Some(m) = &mut map {
-            m.push((Key::from_slice(key.slice), item));
-        } else {
-            let mut m = Vec::with_capacity(DEFAULT_VEC_CAPACITY);
-            m.push((Key::from_slice(key.slice), item));
-            map = Some(m);
-        }
+    // Separator
+    index = skip_spaces::<VALIDATE_UTF8>(bytes, index)?;
+    if bytes[index] != b':' {
+        return Err(JsonError::new(bytes, index));
     }
+
+    // Value
+    Ok(Step::Value(skip_spaces::<VALIDATE_UTF8>(bytes, index + 1)?))
 }
 
+// Continues parsing an array, up to its next value or its end
 #[inline(always)]
-fn parse_array(bytes: &[u8], mut index: usize) -> Result<JsonItem, JsonError> {
-    let mark = index;
-    let mut array = None;
-    index += 1;
-    loop {
-        // Spaces
-        index = skip_spaces(bytes, index)?;
+fn parse_array<'a, const VALIDATE_UTF8: bool>(bytes: &'a [u8], mut index: usize, mark: usize, array: &mut Option<Vec<JsonItem<'a>>>, options: &ParseOptions) -> Result<Step<'a>, JsonError> {
+    // Spaces
+    index = skip_spaces::<VALIDATE_UTF8>(bytes, index)?;
 
-        // Check ending
-        match bytes[index] {
-            b']' => {
-                return Ok(JsonItem::new_array(Slice::from_bytes(bytes, mark, index + 1), array));
-            }
-            b',' => {
-                index = skip_spaces(bytes, index + 1)?;
-            }
-            _ => {
-                if !array.is_none() {
-                    return Err(JsonError::new(bytes, index));
-                }
+    // Check ending
+    match bytes[index] {
+        b']' => {
+            let item = JsonItem::new_array(Slice::from_bytes(bytes, mark, index + 1), array.take());
+            return Ok(Step::Item(item, index + 1));
+        }
+        b',' if array.is_some() => {
+            index = skip_spaces::<VALIDATE_UTF8>(bytes, index + 1)?;
+        }
+        _ => {
+            if array.is_some() {
+                return Err(JsonError::new(bytes, index));
             }
         }
+    }
 
-        // Item
-        let item = parse_item(bytes, index)?;
-        index += shift_index(&item);
-
-        // Store
-        if let Some(a) = &mut array {
-            a.push(item);
-        } else {
-            let mut a = Vec::with_capacity(DEFAULT_VEC_CAPACITY);
-            a.push(item);
-            array = Some(a);
-        }
+    // Value
+    if array.as_ref().map_or(0, Vec::len) >= options.max_array_length {
+        return Err(JsonError::with_kind(bytes, index, ArrayTooLong));
     }
+    Ok(Step::Value(index))
 }
 
 /// Main library function. Parses JSON data.
+/// The root can be any JSON value, and must only be followed by whitespace.
 ///
 /// # Arguments
 /// * `source` - Text content to be parsed
@@ -213,31 +392,133 @@ fn parse_array(bytes: &[u8], mut index: usize) -> Result<JsonItem, JsonError> {
 /// # Example
 ///
 /// ```rust
-/// fn main() {
-///    let json = "{\"jsonic\": \"Fast, small JSON parsing library for rust with no dependencies\"}";
+/// let json = "{\"jsonic\": \"Fast, small JSON parsing library for rust with no dependencies\"}";
 ///
-///    match jsonic::parse(json) {
-///        Ok(parsed) => { println!("Describe jsonic? {:?}", parsed["jsonic"].as_str()); }
-///        Err(error) => { eprintln!("{}", error); }
-///    }
+/// match jsonic::parse(json) {
+///     Ok(parsed) => { println!("Describe jsonic? {:?}", parsed["jsonic"].as_str()); }
+///     Err(error) => { eprintln!("{}", error); }
 /// }
-pub fn parse(source: &str) -> Result<JsonItem, JsonError> {
-    let bytes = source.as_bytes();
-    let mut index = 0_usize;
-    index = skip_spaces(bytes, index)?;
-    match bytes[index] {
-        b'{' => { parse_map(bytes, index) }
-        b'[' => { parse_array(bytes, index) }
-        _ => { Err(JsonError::new(bytes, index)) }
+/// ```
+///
+/// The parsed item borrows from `source`, so the source text must outlive it:
+///
+/// ```compile_fail
+/// let parsed = {
+///     let json = String::from("{\"a\": \"b\"}");
+///     jsonic::parse(&json).unwrap()
+/// };
+/// println!("{:?}", parsed["a"].as_str());
+/// ```
+pub fn parse(source: &str) -> Result<JsonItem<'_>, JsonError> {
+    parse_root::<false>(source.as_bytes(), &DEFAULT_OPTIONS)
+}
+
+/// Parses JSON data, using the provided options.
+///
+/// # Arguments
+/// * `source` - Text content to be parsed
+/// * `options` - Parsing options
+///
+/// # Example
+///
+/// ```rust
+/// use jsonic::json_error::JsonErrorKind;
+/// use jsonic::parse_options::{DuplicateKeys, ParseOptions};
+///
+/// let options = ParseOptions::new().duplicate_keys(DuplicateKeys::Error);
+///
+/// let error = jsonic::parse_with("{\"a\": 1, \"a\": 2}", &options).unwrap_err();
+/// assert_eq!(error.get_kind(), &JsonErrorKind::DuplicateKey("a".to_owned()));
+/// ```
+pub fn parse_with<'a>(source: &'a str, options: &ParseOptions) -> Result<JsonItem<'a>, JsonError> {
+    parse_root::<false>(source.as_bytes(), options)
+}
+
+/// Parses the first JSON value found in data, ignoring anything after it.
+/// Returns the root item and the number of bytes used, leading whitespace included, so that further values can be extracted from the remaining data.
+///
+/// # Arguments
+/// * `source` - Text content starting with a JSON value
+///
+/// # Example
+///
+/// ```rust
+/// let stream = "{\"id\": 1} {\"id\": 2}";
+///
+/// let (first, used) = jsonic::parse_prefix(stream).unwrap();
+/// assert_eq!(first["id"].as_i128(), Some(1));
+///
+/// let (second, _) = jsonic::parse_prefix(&stream[used..]).unwrap();
+/// assert_eq!(second["id"].as_i128(), Some(2));
+/// ```
+pub fn parse_prefix(source: &str) -> Result<(JsonItem<'_>, usize), JsonError> {
+    parse_value::<false>(source.as_bytes(), &DEFAULT_OPTIONS)
+}
+
+/// Parses JSON data from raw bytes, which are checked to be valid UTF-8 while parsing.
+/// A leading UTF-8 byte order mark is skipped. Data in other Unicode encodings can be converted beforehand using [`encoding::transcode`].
+///
+/// # Arguments
+/// * `source` - UTF-8 content to be parsed
+///
+/// # Example
+///
+/// ```rust
+/// let json = b"\xef\xbb\xbf{\"caf\xc3\xa9\": true}";
+///
+/// let parsed = jsonic::parse_bytes(json).unwrap();
+/// assert_eq!(parsed["café"].as_bool(), Some(true));
+///
+/// let error = jsonic::parse_bytes(b"[\"caf\xe9\"]").unwrap_err();
+/// assert_eq!(error.get_index(), 5);
+/// ```
+pub fn parse_bytes(source: &[u8]) -> Result<JsonItem<'_>, JsonError> {
+    parse_root::<true>(source, &DEFAULT_OPTIONS)
+}
+
+/// Parses JSON data from raw bytes, using the provided options. See [`parse_bytes`] and [`parse_with`].
+pub fn parse_bytes_with<'a>(source: &'a [u8], options: &ParseOptions) -> Result<JsonItem<'a>, JsonError> {
+    parse_root::<true>(source, options)
+}
+
+// Raw bytes are checked to be valid UTF-8 while parsing, which `&str` input is already
+#[inline(always)]
+fn parse_root<'a, const VALIDATE_UTF8: bool>(bytes: &'a [u8], options: &ParseOptions) -> Result<JsonItem<'a>, JsonError> {
+    let (root, mut index) = parse_value::<VALIDATE_UTF8>(bytes, options)?;
+    while index < bytes.len() {
+        match bytes[index] {
+            b' ' | b'\n' | b'\r' | b'\t' => { index += 1; }
+            _ => { return Err(JsonError::with_kind(bytes, index, TrailingCharacters)); }
+        }
     }
+    Ok(root)
+}
+
+#[inline(always)]
+fn parse_value<'a, const VALIDATE_UTF8: bool>(bytes: &'a [u8], options: &ParseOptions) -> Result<(JsonItem<'a>, usize), JsonError> {
+    if bytes.len() > options.max_input_size {
+        return Err(JsonError::with_kind(bytes, options.max_input_size, InputTooLarge));
+    }
+    let mut index = if bytes.starts_with(UTF8_BOM) { UTF8_BOM.len() } else { 0 };
+    index = skip_spaces::<VALIDATE_UTF8>(bytes, index)?;
+    let root = parse_item::<VALIDATE_UTF8>(bytes, index, options)?;
+    index += shift_index(&root);
+    Ok((root, index))
 }
 
 #[cfg(test)]
+#[allow(clippy::assertions_on_constants)]
 mod tests {
-    use crate::parse;
+    use std::borrow::Cow;
+
+    use crate::json_error::JsonErrorKind;
+    use crate::parse_options::{DuplicateKeys, NumberDecoding, ParseOptions};
+    use crate::unescape::LoneSurrogates;
+    use crate::{parse, parse_bytes, parse_prefix, parse_with};
 
     const CORRECT_JSON: &str = " {\n\"test\": \"why not?\",\"b\": true,\"another one\":  \"hey#çà@â&éè\" \r ,\"obj2\":{\"k\":{\"k2\":\"v\"}}, \"num\":4.2344, \"int\":-234,  \"obj\":{\"a\":\"b\", \"c\":\"d\"}, \"arr\":[1,2,3],\"bool\":false, \"exp\":3.3e-21, \"exp2\":-4.5e-213,\"exp3\":3.7391238e+24,\"depth\":[\"a\",[\"b\",\"c\"]],\"emp_a\":[],\"emp_m\":{}}  ";
     const INCORRECT_JSON: &str = "{\"test\": \"num\", \"int\":234[] ,,}";
+    const TRAILING_JSON: &str = "{\"a\": 1} xyz";
 
     #[test]
     fn parse_correct() {
@@ -426,7 +707,7 @@ mod tests {
     fn missing_key() {
         match parse(CORRECT_JSON) {
             Ok(parsed) => {
-                assert_eq!(parsed["a"].exists(), false);
+                assert!(!parsed["a"].exists());
             }
             Err(error) => {
                 assert!(false, "{}", error.to_string());
@@ -482,4 +763,576 @@ mod tests {
             }
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn parse_bytes_utf8() {
+        match parse_bytes(CORRECT_JSON.as_bytes()) {
+            Ok(parsed) => {
+                assert_eq!(parsed["another one"].as_str(), Some("hey#çà@â&éè"));
+            }
+            Err(error) => {
+                assert!(false, "{}", error.to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn parse_bytes_bom() {
+        match parse_bytes(b"\xef\xbb\xbf [\"\xe2\x82\xac\"]") {
+            Ok(parsed) => {
+                assert_eq!(parsed[0].as_str(), Some("€"));
+            }
+            Err(error) => {
+                assert!(false, "{}", error.to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn parse_bytes_invalid_utf8() {
+        let invalid: [&[u8]; 6] = [b"[\"\xc3\"]", b"[\"a\xc0\xafb\"]", b"[\"\xed\xa0\x80\"]", b"[\"\xf4\x90\x80\x80\"]", b"[\"\xe2\x82", b"[1, \xff]"];
+        let indexes = [2, 3, 2, 2, 2, 4];
+        for (bytes, index) in invalid.iter().zip(indexes) {
+            match parse_bytes(bytes) {
+                Ok(_) => {
+                    assert!(false);
+                }
+                Err(error) => {
+                    assert_eq!(error.get_kind(), &JsonErrorKind::InvalidUtf8);
+                    assert_eq!(error.get_index(), index);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn parse_numbers() {
+        match parse("[0, -0, 12, -3.25, 1e5, 2E-3, 0.5e+12, -0.0]") {
+            Ok(parsed) => {
+                assert_eq!(parsed.elements().unwrap().count(), 8);
+                assert_eq!(parsed[6].as_f64(), Some(0.5e12));
+            }
+            Err(error) => {
+                assert!(false, "{}", error.to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn parse_invalid_numbers() {
+        let invalid = ["[+1]", "[1-2-3]", "[01]", "[1.]", "[--]", "[-]", "[1.e5]", "[1e]", "[1e+]", "[.5]", "[1.5.2]"];
+        let indexes = [1, 2, 2, 3, 2, 2, 3, 3, 4, 1, 4];
+        for (json, index) in invalid.iter().zip(indexes) {
+            match parse(json) {
+                Ok(_) => {
+                    assert!(false, "{}", json);
+                }
+                Err(error) => {
+                    assert_eq!(error.get_index(), index, "{}", json);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn parse_escaped_strings() {
+        match parse("{\"a\\\\\": \"b\\\\\", \"c\": [\"\\\"\\/\\b\\f\\n\\r\\t\\u00e9\\uD83D\\ude00\"]}") {
+            Ok(parsed) => {
+                assert_eq!(parsed.entries().unwrap().count(), 2);
+                assert_eq!(parsed["c"][0].as_str(), Some("\\\"\\/\\b\\f\\n\\r\\t\\u00e9\\uD83D\\ude00"));
+            }
+            Err(error) => {
+                assert!(false, "{}", error.to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn parse_invalid_strings() {
+        let invalid = ["[\"a\\\"]", "[\"\\q\"]", "[\"\\u12\"]", "[\"\\u12G4\"]", "[\"a\tb\"]", "[\"a\nb\"]", "{\"k\\x\": 1}", "{\"k\u{1}\": 1}", "{k: 1}"];
+        let kinds = [JsonErrorKind::Syntax, JsonErrorKind::InvalidEscape, JsonErrorKind::InvalidEscape, JsonErrorKind::InvalidEscape, JsonErrorKind::ControlCharacter,
+            JsonErrorKind::ControlCharacter, JsonErrorKind::InvalidEscape, JsonErrorKind::ControlCharacter, JsonErrorKind::Syntax];
+        let indexes = [6, 3, 6, 6, 3, 3, 4, 3, 1];
+        for ((json, kind), index) in invalid.iter().zip(kinds).zip(indexes) {
+            match parse(json) {
+                Ok(_) => {
+                    assert!(false, "{}", json);
+                }
+                Err(error) => {
+                    assert_eq!(error.get_kind(), &kind, "{}", json);
+                    assert_eq!(error.get_index(), index, "{}", json);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn parse_scalar_roots() {
+        let roots = ["\"text\"", " 42 ", "-1.5e3", "null", "true", "false\n"];
+        for json in roots {
+            match parse(json) {
+                Ok(parsed) => {
+                    assert_eq!(parsed.as_str(), Some(json.trim().trim_matches('"')));
+                }
+                Err(error) => {
+                    assert!(false, "{}", error.to_string());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn parse_trailing() {
+        match parse(TRAILING_JSON) {
+            Ok(_) => {
+                assert!(false);
+            }
+            Err(error) => {
+                assert_eq!(error.get_kind(), &JsonErrorKind::TrailingCharacters);
+                assert_eq!(error.get_index(), 9);
+            }
+        }
+    }
+
+    #[test]
+    fn parse_prefix_used() {
+        match parse_prefix(TRAILING_JSON) {
+            Ok((parsed, used)) => {
+                assert_eq!(parsed["a"].as_i128(), Some(1));
+                assert_eq!(used, 8);
+            }
+            Err(error) => {
+                assert!(false, "{}", error.to_string());
+            }
+        }
+        match parse_prefix("  12 34") {
+            Ok((parsed, used)) => {
+                assert_eq!(parsed.as_i128(), Some(12));
+                assert_eq!(used, 4);
+            }
+            Err(error) => {
+                assert!(false, "{}", error.to_string());
+            }
+        }
+    }
+
+    fn duplicate_keys_json(entries: usize) -> String {
+        let mut json = String::from("{\"dup\": \"first\"");
+        for i in 0..entries {
+            json.push_str(&format!(", \"k{}\": {}", i, i));
+        }
+        json.push_str(", \"dup\": \"last\"}");
+        json
+    }
+
+    #[test]
+    fn duplicate_keys_policies() {
+        for entries in [2, 200] {
+            let json = duplicate_keys_json(entries);
+            let policies = [(DuplicateKeys::FirstWins, "first"), (DuplicateKeys::LastWins, "last")];
+            for (policy, expected) in policies {
+                match parse_with(&json, &ParseOptions::new().duplicate_keys(policy)) {
+                    Ok(parsed) => {
+                        assert_eq!(parsed["dup"].as_str(), Some(expected));
+                        assert_eq!(parsed.entries().unwrap().filter(|(k, _)| k.as_str() == "dup").count(), 1);
+                        assert_eq!(parsed.entries().unwrap().count(), entries + 1);
+                        assert_eq!(parsed.entry_at(0).map(|(k, v)| (k.as_str(), v.as_str())), Some(("dup", Some(expected))));
+                    }
+                    Err(error) => {
+                        assert!(false, "{}", error.to_string());
+                    }
+                }
+            }
+            let result = parse_with(&json, &ParseOptions::new().duplicate_keys(DuplicateKeys::Error));
+            match result {
+                Ok(_) => {
+                    assert!(false);
+                }
+                Err(error) => {
+                    assert_eq!(error.get_kind(), &JsonErrorKind::DuplicateKey("dup".to_owned()));
+                    assert_eq!(error.get_index(), json.rfind("\"dup\"").unwrap());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn large_map_order() {
+        let keys: Vec<String> = (0..100).map(|i| format!("k{}", (i * 37) % 100)).collect();
+        let json = format!("{{{}}}", keys.iter().enumerate().map(|(i, key)| format!("\"{}\": {}", key, i)).collect::<Vec<_>>().join(", "));
+        match parse(&json) {
+            Ok(parsed) => {
+                let order: Vec<&str> = parsed.keys().unwrap().map(|key| key.as_str()).collect();
+                assert_eq!(order, keys);
+                for (i, key) in keys.iter().enumerate() {
+                    assert_eq!(parsed[key.as_str()].as_i128(), Some(i as i128));
+                    assert_eq!(parsed.entry_at(i).map(|(k, _)| k.as_str()), Some(key.as_str()));
+                }
+                assert!(parsed.entry_at(100).is_none());
+                assert!(parsed.get("k100").is_none());
+            }
+            Err(error) => {
+                assert!(false, "{}", error.to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn duplicate_keys_default() {
+        for entries in [2, 200] {
+            match parse(&duplicate_keys_json(entries)) {
+                Ok(parsed) => {
+                    assert_eq!(parsed["dup"].as_str(), Some("first"));
+                }
+                Err(error) => {
+                    assert!(false, "{}", error.to_string());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn parse_deep_nesting() {
+        let depth = 500_000;
+        let arrays = "[".repeat(depth) + &"]".repeat(depth);
+        let maps = "{\"a\":".repeat(depth) + "null" + &"}".repeat(depth);
+        for json in [arrays, maps] {
+            match parse(&json) {
+                Ok(parsed) => {
+                    assert!(parsed.exists());
+                }
+                Err(error) => {
+                    assert!(false, "{}", error.to_string());
+                }
+            }
+        }
+        match parse(&"[{\"a\":".repeat(depth)) {
+            Ok(_) => {
+                assert!(false);
+            }
+            Err(error) => {
+                assert_eq!(error.get_index(), depth * 6);
+            }
+        }
+    }
+
+    #[test]
+    fn parse_leading_comma() {
+        for json in ["{,\"a\": 1}", "[,1]", "[1,]", "{\"a\": 1,}"] {
+            match parse(json) {
+                Ok(_) => {
+                    assert!(false, "{}", json);
+                }
+                Err(_) => {
+                    assert!(true);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn parse_limits() {
+        let json = "{\"a\": [1, 2, 3], \"b\": {\"c\": \"long string\"}}";
+        let limits = [
+            (ParseOptions::new().max_input_size(json.len() - 1), JsonErrorKind::InputTooLarge, json.len() - 1),
+            (ParseOptions::new().max_depth(1), JsonErrorKind::DepthLimitExceeded, 6),
+            (ParseOptions::new().max_string_length(10), JsonErrorKind::StringTooLong, 28),
+            (ParseOptions::new().max_array_length(2), JsonErrorKind::ArrayTooLong, 13),
+            (ParseOptions::new().max_object_entries(1), JsonErrorKind::ObjectTooLarge, 17),
+            (ParseOptions::new().max_nodes(6), JsonErrorKind::TooManyNodes, 28),
+        ];
+        for (options, kind, index) in limits {
+            match parse_with(json, &options) {
+                Ok(_) => {
+                    assert!(false, "{:?}", kind);
+                }
+                Err(error) => {
+                    assert_eq!(error.get_kind(), &kind);
+                    assert_eq!(error.get_index(), index, "{:?}", kind);
+                }
+            }
+        }
+        let options = ParseOptions::new().max_input_size(json.len()).max_depth(2).max_string_length(11).max_array_length(3).max_object_entries(2).max_nodes(7);
+        match parse_with(json, &options) {
+            Ok(parsed) => {
+                assert_eq!(parsed["b"]["c"].as_str(), Some("long string"));
+            }
+            Err(error) => {
+                assert!(false, "{}", error.to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn parse_depth_limit_deep() {
+        let json = "[".repeat(100_000);
+        match parse_with(&json, &ParseOptions::new().max_depth(128)) {
+            Ok(_) => {
+                assert!(false);
+            }
+            Err(error) => {
+                assert_eq!(error.get_kind(), &JsonErrorKind::DepthLimitExceeded);
+                assert_eq!(error.get_index(), 128);
+            }
+        }
+    }
+
+    #[test]
+    fn decode_strings() {
+        match parse("{\"plain\": \"why not?\", \"escaped\": \"caf\\u00e9\\n\\ud83d\\ude00\", \"lone\": \"a\\ud800\", \"num\": 1}") {
+            Ok(parsed) => {
+                assert!(matches!(parsed["plain"].as_string(), Some(Cow::Borrowed("why not?"))));
+                assert_eq!(parsed["escaped"].as_string().as_deref(), Some("café\n😀"));
+                assert_eq!(parsed["lone"].as_string().as_deref(), Some("a\u{fffd}"));
+                assert_eq!(parsed["lone"].as_string_with(LoneSurrogates::Error), None);
+                assert_eq!(parsed["num"].as_string(), None);
+
+                let mut buffer = Vec::new();
+                parsed["escaped"].write_unescaped(&mut buffer).unwrap();
+                assert_eq!(buffer, "café\n😀".as_bytes());
+                buffer.clear();
+                parsed["lone"].write_unescaped_with(&mut buffer, LoneSurrogates::Wtf8).unwrap();
+                assert_eq!(buffer, b"a\xed\xa0\x80");
+                assert!(parsed["lone"].write_unescaped_with(&mut buffer, LoneSurrogates::Error).is_err());
+                assert!(parsed["num"].write_unescaped(&mut buffer).is_err());
+            }
+            Err(error) => {
+                assert!(false, "{}", error.to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn escaped_keys() {
+        for entries in [0, 100] {
+            let mut json = String::from("{\"caf\\u00e9\": 1, \"a\\/b\": 2, \"plain\": 3");
+            for i in 0..entries {
+                json.push_str(&format!(", \"k{}\": {}", i, i));
+            }
+            json.push('}');
+            match parse(&json) {
+                Ok(parsed) => {
+                    assert_eq!(parsed["café"].as_i128(), Some(1));
+                    assert_eq!(parsed["a/b"].as_i128(), Some(2));
+                    assert_eq!(parsed["plain"].as_i128(), Some(3));
+                    assert!(!parsed["caf\\u00e9"].exists());
+                    let decoded: Vec<_> = parsed.entries().unwrap().map(|(k, _)| k.decoded().into_owned()).collect();
+                    assert!(decoded.contains(&"café".to_owned()) && decoded.contains(&"a/b".to_owned()));
+                }
+                Err(error) => {
+                    assert!(false, "{}", error.to_string());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn escaped_duplicate_keys() {
+        match parse_with("{\"a\": 1, \"\\u0061\": 2}", &ParseOptions::new().duplicate_keys(DuplicateKeys::Error)) {
+            Ok(_) => {
+                assert!(false);
+            }
+            Err(error) => {
+                assert_eq!(error.get_kind(), &JsonErrorKind::DuplicateKey("a".to_owned()));
+                assert_eq!(error.get_index(), 9);
+            }
+        }
+    }
+
+    #[test]
+    fn lone_surrogate_keys() {
+        let options = ParseOptions::new().duplicate_keys(DuplicateKeys::Error);
+        let padding: String = (0..100).map(|i| format!(", \"k{}\": {}", i, i)).collect();
+        for json in ["{\"\\ud800\": 1, \"\\udc00\": 2, \"\\ufffd\": 3}".to_owned(), format!("{{\"\\ud800\": 1, \"\\udc00\": 2{}}}", padding)] {
+            match parse_with(&json, &options) {
+                Ok(parsed) => {
+                    assert_eq!(parsed.get("\u{fffd}").and_then(|item| item.as_i64()), if json.len() < 100 { Some(3) } else { None });
+                    assert_eq!(parsed.entries().map(|entries| entries.count()), Some(if json.len() < 100 { 3 } else { 102 }));
+                }
+                Err(error) => {
+                    assert!(false, "{}", error.to_string());
+                }
+            }
+        }
+        match parse_with("{\"\\ud800\": 1, \"\\uD800\": 2}", &options) {
+            Ok(_) => {
+                assert!(false);
+            }
+            Err(error) => {
+                assert_eq!(error.get_index(), 14);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn item_size() {
+        assert_eq!(std::mem::size_of::<crate::json_item::JsonItem<'_>>(), 56);
+    }
+
+    #[test]
+    fn checked_integers() {
+        match parse("[127, -129, 4294967296, -1, 18446744073709551616, 170141183460469231731687303715884105728, 1.0, \"1\"]") {
+            Ok(parsed) => {
+                assert_eq!(parsed[0].as_i32(), Some(127));
+                assert_eq!(parsed[1].as_u32(), None);
+                assert_eq!(parsed[2].as_u32(), None);
+                assert_eq!(parsed[2].as_i64(), Some(4294967296));
+                assert_eq!(parsed[3].as_usize(), None);
+                assert_eq!(parsed[4].as_u64(), None);
+                assert_eq!(parsed[4].as_u128(), Some(18446744073709551616));
+                assert_eq!(parsed[5].as_i128(), None);
+                assert_eq!(parsed[5].as_u128(), Some(170141183460469231731687303715884105728));
+                assert_eq!(parsed[6].as_u64(), None);
+                assert_eq!(parsed[6].as_integer::<u64>(), Some(1));
+                assert_eq!(parsed[7].as_i64(), None);
+                assert_eq!(parsed[7].as_integer::<i64>(), None);
+            }
+            Err(error) => {
+                assert!(false, "{}", error.to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn exact_floats() {
+        match parse("[0.5, 0.1, 9007199254740993, 1e400, 3]") {
+            Ok(parsed) => {
+                assert_eq!(parsed[0].as_f64_exact(), Some(0.5));
+                assert_eq!(parsed[1].as_f64_exact(), None);
+                assert_eq!(parsed[1].as_f64(), Some(0.1));
+                assert_eq!(parsed[2].as_f64_exact(), None);
+                assert_eq!(parsed[3].as_f64_exact(), None);
+                assert_eq!(parsed[4].as_f64_exact(), Some(3.0));
+            }
+            Err(error) => {
+                assert!(false, "{}", error.to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn number_kinds() {
+        match parse("[0, -0, 12345678901234567890123, -1.5, 2e3, 0.0, true]") {
+            Ok(parsed) => {
+                let integers: Vec<bool> = parsed.elements().unwrap().map(|item| item.is_integer()).collect();
+                let floats: Vec<bool> = parsed.elements().unwrap().map(|item| item.is_float()).collect();
+                let negatives: Vec<bool> = parsed.elements().unwrap().map(|item| item.is_negative()).collect();
+                assert_eq!(integers, [true, true, true, false, false, false, false]);
+                assert_eq!(floats, [false, false, false, true, true, true, false]);
+                assert_eq!(negatives, [false, true, false, true, false, false, false]);
+                assert_eq!(parsed[2].as_i128(), Some(12345678901234567890123));
+                assert_eq!(parsed[1].as_u64(), Some(0));
+            }
+            Err(error) => {
+                assert!(false, "{}", error.to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn eager_numbers() {
+        let json = "[0, -0, -12, 9223372036854775807, -9223372036854775808, 18446744073709551615, 18446744073709551616, -1e3, 0.1, 1.7976931348623157e308, 1e400]";
+        let options = ParseOptions::new().number_decoding(NumberDecoding::Eager);
+        match (parse(json), parse_with(json, &options)) {
+            (Ok(lazy), Ok(eager)) => {
+                for (lazy, eager) in lazy.elements().unwrap().zip(eager.elements().unwrap()) {
+                    assert_eq!(lazy.as_f64().map(f64::to_bits), eager.as_f64().map(f64::to_bits), "{:?}", lazy.as_str());
+                    assert_eq!(lazy.as_i128(), eager.as_i128(), "{:?}", lazy.as_str());
+                    assert_eq!(lazy.as_u64(), eager.as_u64(), "{:?}", lazy.as_str());
+                    assert_eq!(lazy.as_i32(), eager.as_i32(), "{:?}", lazy.as_str());
+                    assert_eq!(lazy.as_integer::<i64>(), eager.as_integer::<i64>(), "{:?}", lazy.as_str());
+                    assert_eq!(lazy.is_integer(), eager.is_integer(), "{:?}", lazy.as_str());
+                }
+                assert_eq!(eager[5].as_u64(), Some(u64::MAX));
+                assert_eq!(eager[6].as_u128(), Some(1 << 64));
+            }
+            (Err(error), _) | (_, Err(error)) => {
+                assert!(false, "{}", error.to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn spans() {
+        let json = b"\xef\xbb\xbf {\"k\": [null, -1.5e3, \"\"]} ";
+        match parse_bytes(json) {
+            Ok(parsed) => {
+                assert_eq!(parsed.span(json), Some(4..29));
+                let (key, _) = parsed.entries().unwrap().next().unwrap();
+                assert_eq!(key.span(json), Some(5..8));
+                let spans: Vec<_> = parsed["k"].elements().unwrap().filter_map(|item| item.span(json)).collect();
+                assert_eq!(spans, [11..15, 17..23, 25..27]);
+                assert!(parsed.span("{\"k\": []}").is_none());
+                assert!(key.span(&json[8..]).is_none());
+                assert!(key.span(&json[6..]).is_none());
+                assert!(key.span(&json[..7]).is_none());
+                assert!(parsed["k"][2].span(&json[26..]).is_none());
+                assert_eq!(parsed["k"][2].span(&json[25..]), Some(0..2));
+            }
+            Err(error) => {
+                assert!(false, "{}", error.to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn locate_and_path_of() {
+        for entries in [2, 200] {
+            let json = duplicate_keys_json(entries).replace("\"last\"", "[\"last\", {\"x~\": \"\"}]");
+            match parse_with(&json, &ParseOptions::new().duplicate_keys(DuplicateKeys::LastWins)) {
+                Ok(parsed) => {
+                    for offset in 0..json.len() {
+                        let (path, item) = parsed.locate(&json, offset).unwrap();
+                        assert!(path.evaluate(&parsed).is_some_and(|found| std::ptr::eq(found, item)));
+                        assert_eq!(parsed.path_of(item), Some(path));
+                    }
+                    let (path, item) = parsed.locate(&json, json.rfind('"').unwrap()).unwrap();
+                    assert_eq!((path.to_string().as_str(), item.as_str()), ("/dup/1/x~0", Some("")));
+                    let (path, _) = parsed.locate(&json, json.find("k0").unwrap()).unwrap();
+                    assert_eq!(path.to_string(), "/k0");
+                    assert!(parsed.locate(&json, json.len()).is_none());
+                    assert!(parsed.path_of(&parsed["none"]).is_none());
+                }
+                Err(error) => {
+                    assert!(false, "{}", error.to_string());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn get_api() {
+        match parse("{\"a\": [1, 2, 3], \"b\": {\"c\": null, \"d\": \"e\"}, \"f\": {}}") {
+            Ok(parsed) => {
+                assert!(parsed.get("a").is_some());
+                assert!(parsed.get("z").is_none());
+                assert!(parsed["b"].contains_key("c"));
+                assert!(!parsed["b"].contains_key("e"));
+                assert!(parsed["b"].get("c").is_some_and(|item| item.is_null()));
+                assert_eq!(parsed["a"].get_index(2).and_then(|item| item.as_i128()), Some(3));
+                assert!(parsed["a"].get_index(3).is_none());
+                assert!(parsed.get_index(0).is_none());
+                assert_eq!((parsed.len(), parsed["a"].len(), parsed["f"].len(), parsed["z"].len()), (3, 3, 0, 0));
+                assert!(parsed["f"].is_empty() && !parsed.is_empty());
+                let keys: Vec<&str> = parsed.keys().unwrap().map(|key| key.as_str()).collect();
+                assert_eq!(keys, ["a", "b", "f"]);
+                let values: Vec<bool> = parsed["b"].values().unwrap().rev().map(|item| item.is_null()).collect();
+                assert_eq!(values, [false, true]);
+                assert!(parsed["a"].keys().is_none());
+                assert!(parsed["f"].values().is_some_and(|mut values| values.next().is_none()));
+
+                let mut elements = parsed["a"].elements().unwrap();
+                assert_eq!(elements.len(), 3);
+                assert_eq!(elements.next_back().and_then(|item| item.as_i128()), Some(3));
+                assert_eq!(elements.next().and_then(|item| item.as_i128()), Some(1));
+                assert_eq!(elements.len(), 1);
+                assert!(elements.next().is_some() && elements.next().is_none() && elements.next().is_none());
+                assert_eq!(parsed.entries().unwrap().next_back().map(|(key, _)| key.as_str()), Some("f"));
+            }
+            Err(error) => {
+                assert!(false, "{}", error.to_string());
+            }
+        }
+    }
+}
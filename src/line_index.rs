@@ -0,0 +1,169 @@
+// Columns are counted from checkpoints sampled along the source rather than from line starts, so that lookups stay
+// fast on long lines, such as minified JSON
+const CHECKPOINT_INTERVAL: usize = 64;
+
+/// Unit in which columns are counted
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum ColumnUnit {
+    /// Bytes of UTF-8 text
+    Bytes,
+    /// Unicode scalar values
+    #[default]
+    Chars,
+    /// UTF-16 code units, as used by editors following the Language Server Protocol
+    Utf16,
+}
+
+/// A 1-based line and column position in source text
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Converts byte offsets in source text, like item spans or error indexes, into lines and columns.
+/// Lines end with `\n`, `\r\n` or `\r`. The index is built once, each lookup then takes logarithmic time: columns are
+/// counted from checkpoints sampled every few bytes, or are byte offsets for ASCII text.
+///
+/// ```rust
+/// use jsonic::line_index::{ColumnUnit, LineIndex, Position};
+///
+/// let json = "{\n  \"emoji\": \"😀\",\n  \"a\": true\n}";
+/// let parsed = jsonic::parse(json).unwrap();
+/// let span = parsed["a"].span(json).unwrap();
+///
+/// let index = LineIndex::new(json);
+/// assert_eq!(index.position(span.start), Some(Position { line: 3, column: 8 }));
+///
+/// let offset = json.find(',').unwrap();
+/// assert_eq!(index.position(offset), Some(Position { line: 2, column: 15 }));
+/// let index = LineIndex::with_unit(json, ColumnUnit::Utf16);
+/// assert_eq!(index.position(offset), Some(Position { line: 2, column: 16 }));
+/// ```
+#[derive(Debug, Clone)]
+pub struct LineIndex<'a> {
+    source: &'a str,
+    line_starts: Vec<usize>,
+    unit: ColumnUnit,
+    // Byte offsets at char boundaries, with the number of units before them. Empty when units are bytes.
+    checkpoints: Vec<(usize, usize)>,
+}
+
+impl<'a> LineIndex<'a> {
+    /// Indexes lines of source text, counting columns in chars
+    pub fn new(source: &'a str) -> Self {
+        Self::with_unit(source, ColumnUnit::Chars)
+    }
+
+    /// Indexes lines of source text, counting columns in the provided unit
+    pub fn with_unit(source: &'a str, unit: ColumnUnit) -> Self {
+        let bytes = source.as_bytes();
+        let mut line_starts = vec![0];
+        for (index, byte) in bytes.iter().enumerate() {
+            match byte {
+                b'\n' => { line_starts.push(index + 1); }
+                b'\r' if bytes.get(index + 1) != Some(&b'\n') => { line_starts.push(index + 1); }
+                _ => {}
+            }
+        }
+        let mut checkpoints = Vec::new();
+        if unit != ColumnUnit::Bytes && !source.is_ascii() {
+            let mut units = 0;
+            for (index, c) in source.char_indices() {
+                if checkpoints.last().is_none_or(|(last, _)| index >= last + CHECKPOINT_INTERVAL) {
+                    checkpoints.push((index, units));
+                }
+                units += if unit == ColumnUnit::Utf16 { c.len_utf16() } else { 1 };
+            }
+        }
+        LineIndex { source, line_starts, unit, checkpoints }
+    }
+
+    /// Returns the number of lines in source text
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Returns the position of a byte offset. The end of source text is a valid offset.
+    /// Returns `None` if the offset is out of bounds, or not at a char boundary.
+    pub fn position(&self, offset: usize) -> Option<Position> {
+        if !self.source.is_char_boundary(offset) {
+            return None;
+        }
+        let line = self.line_starts.partition_point(|start| *start <= offset);
+        let column = self.units_before(offset) - self.units_before(self.line_starts[line - 1]);
+        Some(Position { line, column: column + 1 })
+    }
+
+    // Number of units before a byte offset at a char boundary, counted from the nearest checkpoint
+    fn units_before(&self, offset: usize) -> usize {
+        if self.checkpoints.is_empty() {
+            return offset;
+        }
+        // The first checkpoint is at offset 0
+        let (start, units) = self.checkpoints[self.checkpoints.partition_point(|(start, _)| *start <= offset) - 1];
+        let prefix = &self.source[start..offset];
+        units + match self.unit {
+            ColumnUnit::Bytes => { prefix.len() }
+            ColumnUnit::Chars => { prefix.chars().count() }
+            ColumnUnit::Utf16 => { prefix.chars().map(char::len_utf16).sum() }
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants)]
+mod tests {
+    use crate::line_index::{ColumnUnit, LineIndex, Position};
+
+    #[test]
+    fn line_breaks() {
+        let index = LineIndex::new("a\nb\r\nc\rd");
+        assert_eq!(index.line_count(), 4);
+        let positions: Vec<(usize, usize)> = (0..=9).filter_map(|offset| index.position(offset)).map(|p| (p.line, p.column)).collect();
+        assert_eq!(positions, [(1, 1), (1, 2), (2, 1), (2, 2), (2, 3), (3, 1), (3, 2), (4, 1), (4, 2)]);
+        assert!(index.position(10).is_none());
+        assert_eq!(LineIndex::new("").position(0), Some(Position { line: 1, column: 1 }));
+    }
+
+    #[test]
+    fn column_units() {
+        let source = "\"é😀\"";
+        let columns = |unit| LineIndex::with_unit(source, unit).position(source.len()).map(|p| p.column);
+        assert_eq!(columns(ColumnUnit::Bytes), Some(9));
+        assert_eq!(columns(ColumnUnit::Chars), Some(5));
+        assert_eq!(columns(ColumnUnit::Utf16), Some(6));
+        assert!(LineIndex::new(source).position(2).is_none());
+    }
+
+    #[test]
+    fn long_lines() {
+        let source = "[\"é😀\", 1]\n".repeat(100) + &"{\"a\": \"ü😀\"}".repeat(1_000);
+        for unit in [ColumnUnit::Bytes, ColumnUnit::Chars, ColumnUnit::Utf16] {
+            let index = LineIndex::with_unit(&source, unit);
+            for (offset, _) in source.char_indices().step_by(7).chain([(source.len(), ' ')]) {
+                let line_start = source[..offset].rfind('\n').map_or(0, |start| start + 1);
+                let prefix = &source[line_start..offset];
+                let column = match unit {
+                    ColumnUnit::Bytes => { prefix.len() }
+                    ColumnUnit::Chars => { prefix.chars().count() }
+                    ColumnUnit::Utf16 => { prefix.encode_utf16().count() }
+                };
+                assert_eq!(index.position(offset), Some(Position { line: source[..offset].matches('\n').count() + 1, column: column + 1 }));
+            }
+        }
+        let ascii = "{\"a\": 1}".repeat(1_000);
+        assert_eq!(LineIndex::new(&ascii).position(ascii.len()), Some(Position { line: 1, column: ascii.len() + 1 }));
+    }
+
+    #[test]
+    fn error_position() {
+        let json = "{\n  \"a\": [1, 2,]\n}";
+        match crate::parse(json) {
+            Ok(_) => { assert!(false); }
+            Err(error) => {
+                assert_eq!(LineIndex::new(json).position(error.get_index()), Some(Position { line: 2, column: 14 }));
+            }
+        }
+    }
+}
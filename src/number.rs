@@ -0,0 +1,658 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::fmt::{Display, Formatter, Write};
+
+use crate::power_of_five::{LARGEST_POWER_OF_FIVE, POWER_OF_FIVE_128, SMALLEST_POWER_OF_FIVE};
+
+// Components of a number, as found in the source text (its grammar was validated when parsing)
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct NumberParts<'a> {
+    pub(crate) negative: bool,
+    pub(crate) integer: &'a str,
+    pub(crate) fraction: &'a str,
+    pub(crate) exponent: i64,
+}
+
+/// Kind of a JSON number, as written in the source data
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum NumberKind {
+    /// Written without fraction nor exponent, like `-12`
+    Integer,
+    /// Written with a fraction or an exponent, like `1.5` or `1e3`
+    Float,
+}
+
+// What the parser found while scanning a number. Digit boundaries are offsets into the number text: the integer part
+// ends at `integer_end`, the fraction (if any) at `fraction_end`, and the exponent (if any) starts right after `fraction_end`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct NumberLayout {
+    pub(crate) kind: NumberKind,
+    pub(crate) negative: bool,
+    pub(crate) integer_end: u32,
+    pub(crate) fraction_end: u32,
+}
+
+// Value of a number decoded while parsing
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum DecodedNumber {
+    Signed(i64),
+    Unsigned(u64),
+    Float(f64),
+}
+
+impl DecodedNumber {
+    // Returns `None` for integers out of range of both `i64` and `u64`, and for `-0` (whose sign would be lost),
+    // which are left to be converted from source text
+    pub(crate) fn new(raw: &str, layout: NumberLayout) -> Option<Self> {
+        match layout.kind {
+            NumberKind::Integer => {
+                let value = match parse_small_integer(raw, layout) {
+                    Some(value) => { value }
+                    None => { raw.parse::<i128>().ok()? }
+                };
+                if value == 0 && layout.negative {
+                    return None;
+                }
+                match i64::try_from(value) {
+                    Ok(value) => { Some(DecodedNumber::Signed(value)) }
+                    Err(_) => { u64::try_from(value).ok().map(DecodedNumber::Unsigned) }
+                }
+            }
+            NumberKind::Float => { to_f64(raw, &NumberParts::with_layout(raw, layout)).map(DecodedNumber::Float) }
+        }
+    }
+}
+
+impl NumberLayout {
+    // Number of digits before the decimal point
+    pub(crate) fn integer_digits(&self) -> usize {
+        self.integer_end as usize - self.negative as usize
+    }
+}
+
+// Significant digits of a non-zero number, borrowed from the integer and fraction digits they span
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Significand<'a> {
+    head: &'a str,
+    tail: &'a str,
+    pub(crate) exponent: i64,
+}
+
+impl<'a> Significand<'a> {
+    pub(crate) fn len(&self) -> usize {
+        self.head.len() + self.tail.len()
+    }
+
+    // ASCII digits, most significant first
+    pub(crate) fn digits(&self) -> impl Iterator<Item = u8> + 'a {
+        self.head.bytes().chain(self.tail.bytes())
+    }
+}
+
+impl<'a> NumberParts<'a> {
+    pub(crate) fn new(raw: &'a str) -> Self {
+        let (negative, unsigned) = match raw.strip_prefix('-') {
+            Some(unsigned) => { (true, unsigned) }
+            None => { (false, raw) }
+        };
+        let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+            Some(index) => { (&unsigned[..index], parse_exponent(&unsigned[index + 1..])) }
+            None => { (unsigned, 0) }
+        };
+        let (integer, fraction) = match mantissa.split_once('.') {
+            Some((integer, fraction)) => { (integer, fraction) }
+            None => { (mantissa, "") }
+        };
+        NumberParts { negative, integer, fraction, exponent }
+    }
+
+    // Same as `new`, without scanning the text again
+    pub(crate) fn with_layout(raw: &'a str, layout: NumberLayout) -> Self {
+        let negative = layout.negative;
+        let (integer_end, fraction_end) = (layout.integer_end as usize, layout.fraction_end as usize);
+        let integer = &raw[negative as usize..integer_end];
+        let fraction = if fraction_end > integer_end { &raw[integer_end + 1..fraction_end] } else { "" };
+        let exponent = if fraction_end < raw.len() { parse_exponent(&raw[fraction_end + 1..]) } else { 0 };
+        NumberParts { negative, integer, fraction, exponent }
+    }
+
+    pub(crate) fn kind(&self, raw: &str) -> NumberKind {
+        if self.fraction.is_empty() && !raw.contains(['e', 'E']) {
+            NumberKind::Integer
+        } else {
+            NumberKind::Float
+        }
+    }
+
+    // Significant digits without leading or trailing zeros, and the power of ten to multiply them by.
+    // Returns `None` for zero.
+    pub(crate) fn significand(&self) -> Option<Significand<'a>> {
+        let fraction = self.fraction.trim_end_matches('0');
+        let (integer, fraction) = if fraction.is_empty() { (self.integer.trim_end_matches('0'), "") } else { (self.integer, fraction) };
+        let zeros = (self.integer.len() - integer.len() + self.fraction.len() - fraction.len()) as i64;
+        let exponent = self.exponent.saturating_sub(self.fraction.len() as i64).saturating_add(zeros);
+        let head = integer.trim_start_matches('0');
+        let (head, tail) = if head.is_empty() { ("", fraction.trim_start_matches('0')) } else { (head, fraction) };
+        if tail.is_empty() && head.is_empty() {
+            None
+        } else {
+            Some(Significand { head, tail, exponent })
+        }
+    }
+
+    // Exact integer value, if the number is integral and fits in an i128
+    pub(crate) fn integer_value(&self) -> Option<i128> {
+        let significand = match self.significand() {
+            None => { return Some(0); }
+            Some(significand) => { significand }
+        };
+        let exponent = significand.exponent;
+        if !(0..=38).contains(&exponent) {
+            return None;
+        }
+        let mut value = 0_i128;
+        for b in significand.digits() {
+            value = value.checked_mul(10)?.checked_add((b - b'0') as i128)?;
+        }
+        value = value.checked_mul(10_i128.checked_pow(exponent as u32)?)?;
+        Some(if self.negative { -value } else { value })
+    }
+}
+
+// Saturates: such exponents are way out of range of any representation anyway
+fn parse_exponent(exponent: &str) -> i64 {
+    let (negative, digits) = match exponent.as_bytes()[0] {
+        b'-' => { (true, &exponent[1..]) }
+        b'+' => { (false, &exponent[1..]) }
+        _ => { (false, exponent) }
+    };
+    let value = digits.bytes().fold(0_i64, |value, b| value.saturating_mul(10).saturating_add((b - b'0') as i64));
+    if negative { -value } else { value }
+}
+
+// Fast path for integers written without fraction nor exponent, up to 19 digits so that they always fit in a u64
+#[inline(always)]
+pub(crate) fn parse_small_integer(raw: &str, layout: NumberLayout) -> Option<i128> {
+    if layout.kind != NumberKind::Integer || layout.integer_digits() > 19 {
+        return None;
+    }
+    let digits = &raw.as_bytes()[layout.negative as usize..];
+    let value = digits.iter().fold(0_u64, |value, b| value * 10 + (b - b'0') as u64) as i128;
+    Some(if layout.negative { -value } else { value })
+}
+
+const MANTISSA_EXPLICIT_BITS: i32 = 52;
+const MINIMUM_EXPONENT: i32 = -1023;
+const INFINITE_POWER: i32 = 0x7FF;
+const MIN_EXPONENT_ROUND_TO_EVEN: i64 = -4;
+const MAX_EXPONENT_ROUND_TO_EVEN: i64 = 23;
+const MAX_EXPONENT_FAST_PATH: i64 = 22;
+const MAX_MANTISSA_FAST_PATH: u64 = 2 << MANTISSA_EXPLICIT_BITS;
+const POWERS_OF_TEN: [f64; 23] = [
+    1e0, 1e1, 1e2, 1e3, 1e4, 1e5, 1e6, 1e7, 1e8, 1e9, 1e10, 1e11, 1e12, 1e13, 1e14, 1e15, 1e16, 1e17, 1e18, 1e19, 1e20, 1e21, 1e22,
+];
+
+// Decodes a number to the nearest f64. Up to 19 significant digits are gathered in a u64 and converted with
+// a plain floating point operation when exact, or with the Eisel-Lemire algorithm otherwise. The rare cases
+// Eisel-Lemire cannot decide fall back to the standard library.
+pub(crate) fn to_f64(raw: &str, parts: &NumberParts<'_>) -> Option<f64> {
+    let mut mantissa = 0_u64;
+    let mut count = 0;
+    let mut dropped = 0_i64;
+    let mut truncated = false;
+    for b in parts.integer.bytes().chain(parts.fraction.bytes()) {
+        if count == 0 && b == b'0' {
+            continue;
+        }
+        if count < 19 {
+            mantissa = mantissa * 10 + (b - b'0') as u64;
+            count += 1;
+        } else {
+            dropped += 1;
+            truncated |= b != b'0';
+        }
+    }
+    let exponent = parts.exponent.saturating_sub(parts.fraction.len() as i64).saturating_add(dropped);
+
+    let value = if !truncated {
+        fast_path(mantissa, exponent).or_else(|| eisel_lemire(exponent, mantissa))
+    } else {
+        // Digits were dropped: the value lies between both candidates, which must agree
+        match (eisel_lemire(exponent, mantissa), eisel_lemire(exponent, mantissa + 1)) {
+            (Some(lower), Some(upper)) if lower == upper => { Some(lower) }
+            _ => { None }
+        }
+    };
+    match value {
+        Some(value) => { Some(if parts.negative { -value } else { value }) }
+        None => { raw.parse::<f64>().ok() }
+    }
+}
+
+// Both the mantissa and the power of ten are exactly representable, so a single rounding occurs
+#[inline(always)]
+fn fast_path(mantissa: u64, exponent: i64) -> Option<f64> {
+    if mantissa > MAX_MANTISSA_FAST_PATH || !(-MAX_EXPONENT_FAST_PATH..=MAX_EXPONENT_FAST_PATH).contains(&exponent) {
+        None
+    } else if exponent >= 0 {
+        Some(mantissa as f64 * POWERS_OF_TEN[exponent as usize])
+    } else {
+        Some(mantissa as f64 / POWERS_OF_TEN[-exponent as usize])
+    }
+}
+
+// Computes mantissa * 10^exponent rounded to the nearest f64, or `None` if the result cannot be decided
+// from a 128-bit approximation of the power of ten
+fn eisel_lemire(exponent: i64, mantissa: u64) -> Option<f64> {
+    if mantissa == 0 || exponent < SMALLEST_POWER_OF_FIVE {
+        return Some(0.0);
+    } else if exponent > LARGEST_POWER_OF_FIVE {
+        return Some(f64::INFINITY);
+    }
+    let leading_zeros = mantissa.leading_zeros();
+    let normalized = mantissa << leading_zeros;
+    let (low, high) = product_approximation(exponent, normalized, MANTISSA_EXPLICIT_BITS as u32 + 3);
+    if low == u64::MAX && !(-27..=55).contains(&exponent) {
+        return None;
+    }
+    let upper_bit = (high >> 63) as i32;
+    let shift = upper_bit + 64 - MANTISSA_EXPLICIT_BITS - 3;
+    let mut bits = high >> shift;
+    let mut power2 = binary_exponent(exponent as i32) + upper_bit - leading_zeros as i32 - MINIMUM_EXPONENT;
+
+    if power2 <= 0 {
+        // Subnormal
+        if -power2 + 1 >= 64 {
+            return Some(0.0);
+        }
+        bits >>= -power2 + 1;
+        bits += bits & 1;
+        bits >>= 1;
+        power2 = (bits >= (1_u64 << MANTISSA_EXPLICIT_BITS)) as i32;
+        return Some(f64::from_bits(bits | (power2 as u64) << MANTISSA_EXPLICIT_BITS));
+    }
+
+    // Exactly halfway between two floats: round to even
+    if low <= 1 && (MIN_EXPONENT_ROUND_TO_EVEN..=MAX_EXPONENT_ROUND_TO_EVEN).contains(&exponent) && bits & 3 == 1 && bits << shift == high {
+        bits &= !1_u64;
+    }
+    bits += bits & 1;
+    bits >>= 1;
+    if bits >= (2_u64 << MANTISSA_EXPLICIT_BITS) {
+        bits = 1_u64 << MANTISSA_EXPLICIT_BITS;
+        power2 += 1;
+    }
+    bits &= !(1_u64 << MANTISSA_EXPLICIT_BITS);
+    if power2 >= INFINITE_POWER {
+        return Some(f64::INFINITY);
+    }
+    Some(f64::from_bits(bits | (power2 as u64) << MANTISSA_EXPLICIT_BITS))
+}
+
+// floor(log2(10^exponent)) + 63
+#[inline(always)]
+fn binary_exponent(exponent: i32) -> i32 {
+    (exponent.wrapping_mul(152_170 + 65536) >> 16) + 63
+}
+
+// Multiplies the mantissa by the truncated 128-bit power of five, computing the low word only when needed for the given precision
+#[inline(always)]
+fn product_approximation(exponent: i64, mantissa: u64, precision: u32) -> (u64, u64) {
+    let mask = u64::MAX >> precision;
+    let (high5, low5) = POWER_OF_FIVE_128[(exponent - SMALLEST_POWER_OF_FIVE) as usize];
+    let first = mantissa as u128 * high5 as u128;
+    let (mut low, mut high) = (first as u64, (first >> 64) as u64);
+    if high & mask == mask {
+        let second_high = ((mantissa as u128 * low5 as u128) >> 64) as u64;
+        low = low.wrapping_add(second_high);
+        if second_high > low {
+            high += 1;
+        }
+    }
+    (low, high)
+}
+
+// Scientific notation of an f64 with all its significant digits, formatted on the stack
+struct Scientific {
+    bytes: [u8; 800],
+    len: usize,
+}
+
+impl Write for Scientific {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        self.bytes.get_mut(self.len..end).ok_or(fmt::Error)?.copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+// Checks that a finite f64, parsed from the decimal number, has exactly its value
+pub(crate) fn is_exact(value: f64, parts: &NumberParts<'_>) -> bool {
+    let significand = match parts.significand() {
+        None => { return value == 0.0; }
+        Some(significand) => { significand }
+    };
+    // An f64 has at most 767 significant decimal digits
+    if value == 0.0 || !value.is_finite() || significand.len() > 767 {
+        return false;
+    }
+    // Small numbers are decided with integer arithmetic: digits * 10^exponent is an f64 if its denominator is a power
+    // of two and its odd part fits in 53 bits, and is then the parsed value
+    if significand.len() <= 15 && (-22..=22).contains(&significand.exponent) {
+        let digits = significand.digits().fold(0_u128, |digits, b| digits * 10 + (b - b'0') as u128);
+        let power = 5_u128.pow(significand.exponent.unsigned_abs() as u32);
+        let odd = if significand.exponent >= 0 {
+            digits * power
+        } else if digits % power == 0 {
+            digits / power
+        } else {
+            return false;
+        };
+        return odd >> odd.trailing_zeros() < 1 << 53;
+    }
+    // Otherwise compares scientific notations
+    let mut exact = Scientific { bytes: [0; 800], len: 0 };
+    if write!(exact, "{:.767e}", value.abs()).is_err() {
+        return false;
+    }
+    let exact = std::str::from_utf8(&exact.bytes[..exact.len]).unwrap_or_default();
+    let (mantissa, scientific) = exact.split_once('e').unwrap_or((exact, "0"));
+    let scientific = scientific.parse::<i64>().unwrap_or(0);
+    let expected = mantissa.trim_end_matches('0').bytes().filter(|b| *b != b'.');
+    expected.eq(significand.digits()) && significand.exponent.saturating_add(significand.len() as i64 - 1) == scientific
+}
+
+/// Rounding modes for [`JsonNumberValue::to_fixed`]
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum RoundingMode {
+    /// Towards zero
+    Down,
+    /// Away from zero
+    Up,
+    /// Towards negative infinity
+    Floor,
+    /// Towards positive infinity
+    Ceiling,
+    /// To nearest, ties away from zero
+    HalfUp,
+    /// To nearest, ties towards zero
+    HalfDown,
+    /// To nearest, ties to the even neighbour
+    HalfEven,
+}
+
+/// A lossless view of a JSON number, as written in the source data, see [`crate::json_item::JsonItem::as_number`].
+/// Comparisons are exact and numeric: `1`, `1.0` and `10e-1` are equal.
+///
+/// ```rust
+/// use jsonic::number::RoundingMode;
+///
+/// let parsed = jsonic::parse("[12345678901234567890.123456789, 1.5e-1]").unwrap();
+/// let amount = parsed[0].as_number().unwrap();
+///
+/// assert_eq!(amount.integer_digits(), "12345678901234567890");
+/// assert_eq!(amount.fraction_digits(), "123456789");
+/// assert_eq!(amount.to_fixed(2, RoundingMode::HalfEven), Some(1234567890123456789012));
+/// assert_eq!(amount.to_string(), "12345678901234567890.123456789");
+/// assert!(parsed[1].as_number().unwrap() < amount);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct JsonNumberValue<'a> {
+    raw: &'a str,
+    parts: NumberParts<'a>,
+}
+
+impl<'a> JsonNumberValue<'a> {
+    pub(crate) fn new(raw: &'a str, parts: NumberParts<'a>) -> Self {
+        JsonNumberValue { raw, parts }
+    }
+
+    /// Checks if the number is written with a minus sign (`-0` included)
+    pub fn is_negative(&self) -> bool {
+        self.parts.negative
+    }
+
+    /// Digits before the decimal point
+    pub fn integer_digits(&self) -> &'a str {
+        self.parts.integer
+    }
+
+    /// Digits after the decimal point, empty if there is no fraction
+    pub fn fraction_digits(&self) -> &'a str {
+        self.parts.fraction
+    }
+
+    /// Value of the exponent, `0` if there is none. Exponents beyond the range of `i64` are saturated.
+    pub fn exponent(&self) -> i64 {
+        self.parts.exponent
+    }
+
+    /// Original text of the number, for re-serialization
+    pub fn as_str(&self) -> &'a str {
+        self.raw
+    }
+
+    /// Checks if the value of the number is zero
+    pub fn is_zero(&self) -> bool {
+        self.parts.significand().is_none()
+    }
+
+    /// Converts the number to a fixed-scale decimal, i.e. an integer counting units of `10^-scale`, rounded as per `rounding`.
+    /// Returns `None` if the result overflows.
+    pub fn to_fixed(&self, scale: u32, rounding: RoundingMode) -> Option<i128> {
+        let significand = match self.parts.significand() {
+            None => { return Some(0); }
+            Some(significand) => { significand }
+        };
+        let shift = significand.exponent.saturating_add(scale as i64);
+        let len = significand.len() as u64;
+        // Number of trailing digits dropped, which can exceed the number of digits
+        let dropped = if shift < 0 { shift.unsigned_abs() } else { 0 };
+        let mut digits = significand.digits();
+        let mut value = 0_i128;
+        for b in digits.by_ref().take(len.saturating_sub(dropped) as usize) {
+            value = value.checked_mul(10)?.checked_add((b - b'0') as i128)?;
+        }
+        if shift > 0 {
+            value = value.checked_mul(10_i128.checked_pow(u32::try_from(shift).ok()?)?)?;
+        }
+
+        // Dropped digits never end with a zero, so they are never exactly zero
+        if dropped > 0 {
+            let first = if dropped > len { 0 } else { digits.next().map_or(0, |b| b - b'0') };
+            let half = match first {
+                0..=4 => { Ordering::Less }
+                5 if dropped == 1 => { Ordering::Equal }
+                _ => { Ordering::Greater }
+            };
+            let away = match rounding {
+                RoundingMode::Down => { false }
+                RoundingMode::Up => { true }
+                RoundingMode::Floor => { self.parts.negative }
+                RoundingMode::Ceiling => { !self.parts.negative }
+                RoundingMode::HalfUp => { half != Ordering::Less }
+                RoundingMode::HalfDown => { half == Ordering::Greater }
+                RoundingMode::HalfEven => { half == Ordering::Greater || (half == Ordering::Equal && value & 1 == 1) }
+            };
+            if away {
+                value = value.checked_add(1)?;
+            }
+        }
+        Some(if self.parts.negative { -value } else { value })
+    }
+}
+
+impl Display for JsonNumberValue<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.raw)
+    }
+}
+
+impl PartialEq for JsonNumberValue<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for JsonNumberValue<'_> {}
+
+impl PartialOrd for JsonNumberValue<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for JsonNumberValue<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.parts.significand(), other.parts.significand()) {
+            (None, None) => { Ordering::Equal }
+            (None, Some(_)) => { if other.parts.negative { Ordering::Greater } else { Ordering::Less } }
+            (Some(_), None) => { if self.parts.negative { Ordering::Less } else { Ordering::Greater } }
+            (Some(significand), Some(other_significand)) => {
+                if self.parts.negative != other.parts.negative {
+                    return if self.parts.negative { Ordering::Less } else { Ordering::Greater };
+                }
+                // Magnitudes compare on scientific exponents first, then on digits, trailing zeros being trimmed
+                let magnitude = significand.exponent.saturating_add(significand.len() as i64)
+                    .cmp(&other_significand.exponent.saturating_add(other_significand.len() as i64))
+                    .then_with(|| significand.digits().cmp(other_significand.digits()));
+                if self.parts.negative { magnitude.reverse() } else { magnitude }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::number::{eisel_lemire, is_exact, parse_small_integer, to_f64, JsonNumberValue, NumberKind, NumberLayout, NumberParts, RoundingMode};
+
+    fn value(raw: &str) -> JsonNumberValue<'_> {
+        JsonNumberValue::new(raw, NumberParts::new(raw))
+    }
+
+    #[test]
+    fn parts() {
+        let parts = NumberParts::new("-12.340e+5");
+        assert!(parts.negative);
+        assert_eq!((parts.integer, parts.fraction, parts.exponent), ("12", "340", 5));
+        let significand = |raw| NumberParts::new(raw).significand().map(|s| (String::from_utf8(s.digits().collect()).unwrap(), s.exponent));
+        assert_eq!(significand("-12.340e+5"), Some(("1234".to_owned(), 3)));
+        assert_eq!(significand("1200"), Some(("12".to_owned(), 2)));
+        assert_eq!(significand("0.00120"), Some(("12".to_owned(), -4)));
+        assert_eq!(significand("100.00"), Some(("1".to_owned(), 2)));
+        assert_eq!(significand("010.50"), Some(("105".to_owned(), -1)));
+        assert_eq!(significand("0.000"), None);
+    }
+
+    #[test]
+    fn integer_values() {
+        assert_eq!(NumberParts::new("2.0").integer_value(), Some(2));
+        assert_eq!(NumberParts::new("-1e3").integer_value(), Some(-1000));
+        assert_eq!(NumberParts::new("1.5e1").integer_value(), Some(15));
+        assert_eq!(NumberParts::new("1.5").integer_value(), None);
+        assert_eq!(NumberParts::new("1e39").integer_value(), None);
+        assert_eq!(NumberParts::new("0e999999999999999999999").integer_value(), Some(0));
+    }
+
+    #[test]
+    fn exact_floats() {
+        for (raw, exact) in [("0.5", true), ("0.1", false), ("9007199254740992", true), ("9007199254740993", false), ("1e400", false), ("-0.0", true),
+            ("4.9406564584124654e-324", false), ("1.25e-2", false), ("1.125", true), ("1.5", true), ("3e-1", false), ("0.375", true),
+            ("1e22", true), ("1e23", false), ("123456789012345e22", false), ("9007199254740991e1", false), ("0.0000152587890625", true),
+            ("2.2250738585072014e-308", false), ("144115188075855872", true), ("1.0000000000000002", false)] {
+            let value = raw.parse::<f64>().unwrap();
+            assert_eq!(is_exact(value, &NumberParts::new(raw)), exact, "{}", raw);
+        }
+    }
+
+    #[test]
+    fn compare() {
+        let numbers = ["-1e2", "-99.5", "-0", "0.0", "1e-3", "1", "1.0", "10e-1", "1.000000000000000000000000001", "12345678901234567890.1"];
+        for (i, a) in numbers.iter().enumerate() {
+            for (j, b) in numbers.iter().enumerate() {
+                let (a, b) = (value(a), value(b));
+                let expected = match (i, j) {
+                    (2 | 3, 2 | 3) | (5..=7, 5..=7) => { std::cmp::Ordering::Equal }
+                    _ => { i.cmp(&j) }
+                };
+                assert_eq!(a.cmp(&b), expected, "{} {}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn fixed() {
+        let modes = [RoundingMode::Down, RoundingMode::Up, RoundingMode::Floor, RoundingMode::Ceiling, RoundingMode::HalfUp, RoundingMode::HalfDown, RoundingMode::HalfEven];
+        let cases: [(&str, [i128; 7]); 7] = [
+            ("1.25", [12, 13, 12, 13, 13, 12, 12]),
+            ("1.35", [13, 14, 13, 14, 14, 13, 14]),
+            ("-1.25", [-12, -13, -13, -12, -13, -12, -12]),
+            ("1.251", [12, 13, 12, 13, 13, 13, 13]),
+            ("0.004", [0, 1, 0, 1, 0, 0, 0]),
+            ("0.0005", [0, 1, 0, 1, 0, 0, 0]),
+            ("12e1", [1200, 1200, 1200, 1200, 1200, 1200, 1200]),
+        ];
+        for (raw, expected) in cases {
+            for (mode, expected) in modes.iter().zip(expected) {
+                assert_eq!(value(raw).to_fixed(1, *mode), Some(expected), "{} {:?}", raw, mode);
+            }
+        }
+        assert_eq!(value("0.05").to_fixed(1, RoundingMode::HalfEven), Some(0));
+        assert_eq!(value("0.15").to_fixed(1, RoundingMode::HalfEven), Some(2));
+        assert_eq!(value("1e40").to_fixed(0, RoundingMode::Down), None);
+        for mode in [RoundingMode::Down, RoundingMode::HalfEven, RoundingMode::Floor] {
+            assert_eq!(value("1.5e-99999999999999999999").to_fixed(2, mode), Some(0));
+            assert_eq!(value("-1.5e-9223372036854775808").to_fixed(u32::MAX, mode), Some(if mode == RoundingMode::Floor { -1 } else { 0 }));
+            assert_eq!(value("1.5e99999999999999999999").to_fixed(2, mode), None);
+            assert_eq!(value("-1.5e9223372036854775807").to_fixed(u32::MAX, mode), None);
+        }
+        assert_eq!(value("1.5e-99999999999999999999").to_fixed(0, RoundingMode::Up), Some(1));
+        assert_eq!(value("0e99999999999999999999").to_fixed(2, RoundingMode::Up), Some(0));
+    }
+
+    #[test]
+    fn decode_f64() {
+        let edge = [
+            "0", "-0", "1", "0.1", "0.3", "1.7976931348623157e308", "1.7976931348623159e308", "2.2250738585072014e-308",
+            "2.2250738585072011e-308", "4.9406564584124654e-324", "2.4703282292062327e-324", "2.4703282292062328e-324", "1e-400",
+            "1e400", "9007199254740993", "9007199254740992.000000000000000000001", "123456789012345678901234567890e-10",
+            "0.00000000000000000000000000000000000001", "7.3177701707893310e+15", "2.2250738585072012e-308", "1e23", "8.98846567431158e307",
+            "179769313486231580793728971405303415079934132710037826936173778980444968292764750946649017977587207096330286416692887910946555547851940402630657488671505820681908902000708383676273854845817711531764475730270069855571366959622842914819860834936475292719074168444365510704342711559699508093042880177904174497791.9999999999999999999999999999999999999999999999999999999999999999999999",
+        ];
+        assert_eq!(eisel_lemire(-1, 1), Some(0.1));
+        assert_eq!(eisel_lemire(292, 17976931348623157), Some(f64::MAX));
+        assert_eq!(eisel_lemire(-340, 12345), Some(1.2345e-336));
+        for raw in edge {
+            assert_eq!(to_f64(raw, &NumberParts::new(raw)).map(f64::to_bits), raw.parse::<f64>().ok().map(f64::to_bits), "{}", raw);
+        }
+
+        // Pseudo-random mantissas and exponents, compared with the standard library
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        for _ in 0..100_000 {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let digits = format!("{}", state as u128 * 7919);
+            let digits = &digits[..(state % 25) as usize % digits.len() + 1];
+            let (integer, fraction) = digits.split_at((state >> 8) as usize % digits.len() + 1);
+            let exponent = ((state >> 16) % 700) as i64 - 350;
+            let raw = format!("{}{}.{}0e{}", if state & 1 == 1 { "-" } else { "" }, integer, fraction, exponent);
+            assert_eq!(to_f64(&raw, &NumberParts::new(&raw)).map(f64::to_bits), raw.parse::<f64>().ok().map(f64::to_bits), "{}", raw);
+        }
+    }
+
+    #[test]
+    fn small_integers() {
+        let integer = |raw: &str| {
+            let negative = raw.starts_with('-');
+            parse_small_integer(raw, NumberLayout { kind: NumberKind::Integer, negative, integer_end: raw.len() as u32, fraction_end: raw.len() as u32 })
+        };
+        assert_eq!(integer("0"), Some(0));
+        assert_eq!(integer("-1234"), Some(-1234));
+        assert_eq!(integer("9999999999999999999"), Some(9999999999999999999));
+        assert_eq!(integer("-9999999999999999999"), Some(-9999999999999999999));
+        assert_eq!(integer("10000000000000000000"), None);
+        assert_eq!(parse_small_integer("1.0", NumberLayout { kind: NumberKind::Float, negative: false, integer_end: 1, fraction_end: 3 }), None);
+    }
+}
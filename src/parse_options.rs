@@ -0,0 +1,125 @@
+/// Policy applied when an object contains the same key more than once
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum DuplicateKeys {
+    /// The first occurrence of a key is kept, later ones are ignored
+    FirstWins,
+    /// The last occurrence of a key is kept, at the position of the first one
+    LastWins,
+    /// Parsing fails with a [`crate::json_error::JsonErrorKind::DuplicateKey`] error
+    Error,
+}
+
+/// When numbers are converted from their source text, see [`ParseOptions::number_decoding`]
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum NumberDecoding {
+    /// Numbers are converted by each call to an accessor like `as_f64`
+    Lazy,
+    /// Numbers are converted once while parsing and stored as `i64`, `u64` or `f64`, making accessors O(1).
+    /// Integers out of range of `u64` and `i64` are still converted from source text.
+    Eager,
+}
+
+/// Options controlling how JSON data is parsed, see [`crate::parse_with`].
+/// By default no resource limit applies: limits should be set when parsing untrusted input.
+///
+/// ```rust
+/// use jsonic::json_error::JsonErrorKind;
+/// use jsonic::parse_options::{DuplicateKeys, ParseOptions};
+///
+/// let options = ParseOptions::new().duplicate_keys(DuplicateKeys::LastWins);
+///
+/// let parsed = jsonic::parse_with("{\"a\": 1, \"a\": 2}", &options).unwrap();
+/// assert_eq!(parsed["a"].as_i128(), Some(2));
+///
+/// let options = ParseOptions::new()
+///     .max_input_size(1 << 20)
+///     .max_depth(64)
+///     .max_string_length(4096)
+///     .max_array_length(1000)
+///     .max_object_entries(100)
+///     .max_nodes(10_000);
+///
+/// let error = jsonic::parse_with("[1, 2, [3, [4]]]", &options.max_depth(2)).unwrap_err();
+/// assert_eq!(error.get_kind(), &JsonErrorKind::DepthLimitExceeded);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ParseOptions {
+    pub(crate) duplicate_keys: DuplicateKeys,
+    pub(crate) number_decoding: NumberDecoding,
+    pub(crate) max_input_size: usize,
+    pub(crate) max_depth: usize,
+    pub(crate) max_string_length: usize,
+    pub(crate) max_array_length: usize,
+    pub(crate) max_object_entries: usize,
+    pub(crate) max_nodes: usize,
+}
+
+impl ParseOptions {
+    /// Default options: duplicate keys are resolved with [`DuplicateKeys::FirstWins`], numbers are decoded lazily, and no limit is set
+    pub const fn new() -> Self {
+        ParseOptions {
+            duplicate_keys: DuplicateKeys::FirstWins,
+            number_decoding: NumberDecoding::Lazy,
+            max_input_size: usize::MAX,
+            max_depth: usize::MAX,
+            max_string_length: usize::MAX,
+            max_array_length: usize::MAX,
+            max_object_entries: usize::MAX,
+            max_nodes: usize::MAX,
+        }
+    }
+
+    /// Sets the policy applied to duplicate object keys
+    pub const fn duplicate_keys(mut self, policy: DuplicateKeys) -> Self {
+        self.duplicate_keys = policy;
+        self
+    }
+
+    /// Sets when numbers are converted from their source text. Eager decoding slows parsing down, but pays off when most numbers are read.
+    pub const fn number_decoding(mut self, decoding: NumberDecoding) -> Self {
+        self.number_decoding = decoding;
+        self
+    }
+
+    /// Sets the maximum size of source data, in bytes
+    pub const fn max_input_size(mut self, size: usize) -> Self {
+        self.max_input_size = size;
+        self
+    }
+
+    /// Sets the maximum nesting depth of objects and arrays. A scalar root has depth 0, and each container adds 1.
+    pub const fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Sets the maximum length of strings and keys, in bytes of source text (escape sequences included)
+    pub const fn max_string_length(mut self, length: usize) -> Self {
+        self.max_string_length = length;
+        self
+    }
+
+    /// Sets the maximum number of elements in an array
+    pub const fn max_array_length(mut self, length: usize) -> Self {
+        self.max_array_length = length;
+        self
+    }
+
+    /// Sets the maximum number of entries in an object, duplicate keys included
+    pub const fn max_object_entries(mut self, entries: usize) -> Self {
+        self.max_object_entries = entries;
+        self
+    }
+
+    /// Sets the maximum number of items in the whole document, containers included
+    pub const fn max_nodes(mut self, nodes: usize) -> Self {
+        self.max_nodes = nodes;
+        self
+    }
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,46 +1,76 @@
 use std::fmt;
 use std::fmt::{Debug, Formatter};
+use std::marker::PhantomData;
+use std::ops::Range;
 use std::ptr::null;
 use std::slice::from_raw_parts;
 use std::str::from_utf8_unchecked;
 
-pub(crate) struct Slice {
+#[derive(Clone, Copy)]
+pub(crate) struct Slice<'a> {
     pub(crate) ptr: *const u8,
     pub(crate) len: usize,
+    _source: PhantomData<&'a [u8]>,
 }
 
-impl Slice {
-    pub(crate) fn from_str(source: &str) -> Slice {
+impl<'a> Slice<'a> {
+    pub(crate) fn from_str(source: &'a str) -> Slice<'a> {
         Slice {
             ptr: source.as_ptr(),
             len: source.len(),
+            _source: PhantomData,
         }
     }
 
-    pub(crate) fn from_bytes(bytes: &[u8], start: usize, end: usize) -> Slice {
+    pub(crate) fn from_bytes(bytes: &'a [u8], start: usize, end: usize) -> Slice<'a> {
         Slice {
             ptr: unsafe { bytes.as_ptr().byte_add(start) },
             len: end - start,
+            _source: PhantomData,
         }
     }
 
     pub const fn empty() -> Self {
-        Slice { ptr: null(), len: 0 }
+        Slice { ptr: null(), len: 0, _source: PhantomData }
     }
 
-    pub fn as_bytes(&self) -> &[u8] {
-        unsafe { from_raw_parts(self.ptr, self.len) }
+    pub fn as_bytes(&self) -> &'a [u8] {
+        if self.ptr.is_null() {
+            &[]
+        } else {
+            unsafe { from_raw_parts(self.ptr, self.len) }
+        }
     }
 
-    pub fn as_str(&self) -> &str {
+    pub fn as_str(&self) -> &'a str {
         unsafe { from_utf8_unchecked(self.as_bytes()) }
     }
+
+    // Position of the slice in source along with the quotes around it, if it points into it
+    pub(crate) fn quoted_span_in(&self, source: &[u8]) -> Option<Range<usize>> {
+        let start = self.offset_in(source)?.checked_sub(1)?;
+        let end = start + self.len + 2;
+        (end <= source.len()).then_some(start..end)
+    }
+
+    // Position of the slice in source, if it points into it
+    pub(crate) fn offset_in(&self, source: &[u8]) -> Option<usize> {
+        let start = source.as_ptr() as usize;
+        let offset = (self.ptr as usize).checked_sub(start)?;
+        if self.ptr.is_null() || offset + self.len > source.len() {
+            None
+        } else {
+            Some(offset)
+        }
+    }
 }
 
-unsafe impl Sync for Slice {}
+unsafe impl Send for Slice<'_> {}
 
-impl Debug for Slice {
+unsafe impl Sync for Slice<'_> {}
+
+impl Debug for Slice<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "{:?}", self.as_str())
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,176 @@
+use std::borrow::Cow;
+use std::io;
+use std::io::ErrorKind::InvalidData;
+use std::ops::Range;
+
+/// Policy applied to `\uXXXX` escapes encoding a UTF-16 surrogate without its pair, such as `"\uD800"`
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum LoneSurrogates {
+    /// Decoding fails
+    Error,
+    /// The surrogate is replaced with U+FFFD REPLACEMENT CHARACTER
+    Replace,
+    /// The surrogate is encoded as a 3-byte sequence, as per WTF-8. The result is not valid UTF-8, so this is only
+    /// available when writing bytes, other decoding functions treat it as [`LoneSurrogates::Error`].
+    Wtf8,
+}
+
+const REPLACEMENT: &[u8] = "\u{fffd}".as_bytes();
+
+// Escapes were validated by the parser, so hex digits are known to be present
+#[inline(always)]
+fn hex4(raw: &[u8], index: usize) -> u32 {
+    raw[index..index + 4].iter().fold(0, |value, b| (value << 4) | (*b as char).to_digit(16).unwrap_or(0))
+}
+
+// Decodes the escape sequence starting at index, returns its decoded bytes and its length
+#[inline(always)]
+fn decode_escape<'b>(raw: &'b [u8], index: usize, lone_surrogates: LoneSurrogates, buffer: &'b mut [u8; 4]) -> io::Result<(&'b [u8], usize)> {
+    Ok(match raw[index + 1] {
+        b'b' => { (b"\x08", 2) }
+        b'f' => { (b"\x0c", 2) }
+        b'n' => { (b"\n", 2) }
+        b'r' => { (b"\r", 2) }
+        b't' => { (b"\t", 2) }
+        b'u' => {
+            let unit = hex4(raw, index + 2);
+            let low = if (0xd800..0xdc00).contains(&unit) && raw[index + 6..].starts_with(b"\\u") {
+                Some(hex4(raw, index + 8)).filter(|low| (0xdc00..0xe000).contains(low))
+            } else {
+                None
+            };
+            match (low, char::from_u32(unit)) {
+                (Some(low), _) => {
+                    let c = char::from_u32(0x10000 + ((unit - 0xd800) << 10) + (low - 0xdc00)).unwrap_or(char::REPLACEMENT_CHARACTER);
+                    (c.encode_utf8(buffer).as_bytes(), 12)
+                }
+                (None, Some(c)) => { (c.encode_utf8(buffer).as_bytes(), 6) }
+                (None, None) => {
+                    match lone_surrogates {
+                        LoneSurrogates::Error => { return Err(io::Error::new(InvalidData, "lone surrogate in string")); }
+                        LoneSurrogates::Replace => { (REPLACEMENT, 6) }
+                        LoneSurrogates::Wtf8 => {
+                            buffer[0] = 0xe0 | (unit >> 12) as u8;
+                            buffer[1] = 0x80 | ((unit >> 6) & 0x3f) as u8;
+                            buffer[2] = 0x80 | (unit & 0x3f) as u8;
+                            (&buffer[..3], 6)
+                        }
+                    }
+                }
+            }
+        }
+        _ => { (&raw[index + 1..index + 2], 2) }     // \" \\ \/
+    })
+}
+
+// Decodes the escape sequences of raw string content, passing unescaped chunks to write
+pub(crate) fn unescape<W: FnMut(&[u8]) -> io::Result<()>>(raw: &[u8], lone_surrogates: LoneSurrogates, mut write: W) -> io::Result<()> {
+    let mut mark = 0;
+    let mut index = 0;
+    while index < raw.len() {
+        if raw[index] != b'\\' {
+            index += 1;
+            continue;
+        }
+        write(&raw[mark..index])?;
+        let mut buffer = [0; 4];
+        let (decoded, length) = decode_escape(raw, index, lone_surrogates, &mut buffer)?;
+        write(decoded)?;
+        index += length;
+        mark = index;
+    }
+    write(&raw[mark..])
+}
+
+// Bytes of decoded raw string content, lone surrogates being encoded as per WTF-8, so that strings can be compared
+// losslessly without being decoded in memory first. Content known to have no escape sequence is passed as is.
+pub(crate) struct Wtf8Bytes<'a> {
+    raw: &'a [u8],
+    escaped: bool,
+    index: usize,
+    // Remaining bytes of the last decoded escape sequence
+    buffer: [u8; 4],
+    pending: Range<usize>,
+}
+
+impl<'a> Wtf8Bytes<'a> {
+    pub(crate) fn new(raw: &'a [u8], escaped: bool) -> Self {
+        Wtf8Bytes { raw, escaped, index: 0, buffer: [0; 4], pending: 0..0 }
+    }
+}
+
+impl Iterator for Wtf8Bytes<'_> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        if let Some(position) = self.pending.next() {
+            return Some(self.buffer[position]);
+        }
+        let b = *self.raw.get(self.index)?;
+        if b != b'\\' || !self.escaped {
+            self.index += 1;
+            return Some(b);
+        }
+        let mut buffer = [0; 4];
+        let (decoded, length) = decode_escape(self.raw, self.index, LoneSurrogates::Wtf8, &mut buffer).ok()?;
+        self.buffer[..decoded.len()].copy_from_slice(decoded);
+        self.pending = 1..decoded.len();
+        self.index += length;
+        Some(self.buffer[0])
+    }
+}
+
+// Decodes raw string content, borrowing it when it contains no escape sequence
+pub(crate) fn unescape_str(raw: &str, lone_surrogates: LoneSurrogates) -> Option<Cow<'_, str>> {
+    if !raw.as_bytes().contains(&b'\\') {
+        return Some(Cow::Borrowed(raw));
+    }
+    let mut decoded = Vec::with_capacity(raw.len());
+    unescape(raw.as_bytes(), lone_surrogates, |chunk| {
+        decoded.extend_from_slice(chunk);
+        Ok(())
+    }).ok()?;
+    String::from_utf8(decoded).ok().map(Cow::Owned)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use crate::unescape::{unescape, unescape_str, LoneSurrogates, Wtf8Bytes};
+
+    #[test]
+    fn borrow_without_escapes() {
+        assert!(matches!(unescape_str("café", LoneSurrogates::Error), Some(Cow::Borrowed("café"))));
+    }
+
+    #[test]
+    fn decode_escapes() {
+        let decoded = unescape_str(r#"café\n\"\\\/\b\f\r\t😀!"#, LoneSurrogates::Error);
+        assert_eq!(decoded.as_deref(), Some("café\n\"\\/\x08\x0c\r\t😀!"));
+    }
+
+    #[test]
+    fn lone_surrogates() {
+        for raw in [r"a\uD800b", r"a\uDC00b", r"a\uD800A"] {
+            assert_eq!(unescape_str(raw, LoneSurrogates::Error), None);
+            assert_eq!(unescape_str(raw, LoneSurrogates::Wtf8), None);
+        }
+        assert_eq!(unescape_str(r"a\uD800b", LoneSurrogates::Replace).as_deref(), Some("a\u{fffd}b"));
+        assert_eq!(unescape_str(r"\uD800A", LoneSurrogates::Replace).as_deref(), Some("\u{fffd}A"));
+    }
+
+    #[test]
+    fn wtf8_bytes() {
+        for raw in [r#"café\n\"\\\/😀"#, r"a\uD800b\uDC00", r"\ud83d\ude00\u00e9\ufffd", ""] {
+            let mut decoded = Vec::new();
+            assert!(unescape(raw.as_bytes(), LoneSurrogates::Wtf8, |chunk| {
+                decoded.extend_from_slice(chunk);
+                Ok(())
+            }).is_ok());
+            assert_eq!(Wtf8Bytes::new(raw.as_bytes(), true).collect::<Vec<u8>>(), decoded);
+            assert_eq!(Wtf8Bytes::new(raw.as_bytes(), false).collect::<Vec<u8>>(), raw.as_bytes());
+        }
+        assert_eq!(Wtf8Bytes::new(br"\uD800", true).collect::<Vec<u8>>(), [0xed, 0xa0, 0x80]);
+    }
+}
@@ -0,0 +1,272 @@
+use std::ops::ControlFlow;
+use std::ops::ControlFlow::Continue;
+
+use crate::generics::{ArrayIterator, MapIterator};
+use crate::json_item::JsonItem;
+use crate::json_type::JsonType::{Empty, JsonArray, JsonFalse, JsonMap, JsonNull, JsonNumber, JsonString, JsonTrue};
+use crate::key::Key;
+
+/// Callbacks for a depth-first traversal of a parsed tree in document order, driven by [`JsonItem::accept`].
+/// Every callback does nothing by default, and can return `ControlFlow::Break(())` to stop the traversal.
+///
+/// ```rust
+/// use std::ops::ControlFlow;
+/// use jsonic::visitor::Visitor;
+///
+/// #[derive(Default)]
+/// struct Metrics {
+///     strings: usize,
+///     depth: usize,
+///     max_depth: usize,
+/// }
+///
+/// impl Visitor for Metrics {
+///     fn visit_string(&mut self, _: &str) -> ControlFlow<()> {
+///         self.strings += 1;
+///         ControlFlow::Continue(())
+///     }
+///
+///     fn enter_array(&mut self, _: usize) -> ControlFlow<()> {
+///         self.depth += 1;
+///         self.max_depth = self.max_depth.max(self.depth);
+///         ControlFlow::Continue(())
+///     }
+///
+///     fn leave_array(&mut self) -> ControlFlow<()> {
+///         self.depth -= 1;
+///         ControlFlow::Continue(())
+///     }
+/// }
+///
+/// let parsed = jsonic::parse("{\"a\": [\"b\", [\"c\", 1]], \"d\": \"e\"}").unwrap();
+///
+/// let mut metrics = Metrics::default();
+/// assert!(parsed.accept(&mut metrics).is_continue());
+/// assert_eq!((metrics.strings, metrics.max_depth), (3, 2));
+/// ```
+pub trait Visitor {
+    /// Called for `null`
+    fn visit_null(&mut self) -> ControlFlow<()> {
+        Continue(())
+    }
+
+    /// Called for `true` and `false`
+    fn visit_bool(&mut self, _value: bool) -> ControlFlow<()> {
+        Continue(())
+    }
+
+    /// Called for numbers, with their text as found in the source data
+    fn visit_number(&mut self, _value: &str) -> ControlFlow<()> {
+        Continue(())
+    }
+
+    /// Called for strings, with their decoded value. Lone surrogates are replaced with U+FFFD.
+    fn visit_string(&mut self, _value: &str) -> ControlFlow<()> {
+        Continue(())
+    }
+
+    /// Called before the entries of an object, with their number
+    fn enter_object(&mut self, _len: usize) -> ControlFlow<()> {
+        Continue(())
+    }
+
+    /// Called before the value of each object entry
+    fn visit_key(&mut self, _key: &Key<'_>) -> ControlFlow<()> {
+        Continue(())
+    }
+
+    /// Called after the entries of an object
+    fn leave_object(&mut self) -> ControlFlow<()> {
+        Continue(())
+    }
+
+    /// Called before the elements of an array, with their number
+    fn enter_array(&mut self, _len: usize) -> ControlFlow<()> {
+        Continue(())
+    }
+
+    /// Called after the elements of an array
+    fn leave_array(&mut self) -> ControlFlow<()> {
+        Continue(())
+    }
+}
+
+// Children of a container being visited
+enum Children<'i, 'a> {
+    Elements(ArrayIterator<'i, JsonItem<'a>>),
+    Entries(MapIterator<'i, Key<'a>, JsonItem<'a>>),
+}
+
+// The traversal uses an explicit stack, so that nesting depth is not limited by the call stack
+pub(crate) fn accept<'i, 'a, V: Visitor + ?Sized>(item: &'i JsonItem<'a>, visitor: &mut V) -> ControlFlow<()> {
+    let mut stack = Vec::new();
+    visit(item, visitor, &mut stack)?;
+    while let Some(children) = stack.last_mut() {
+        match children {
+            Children::Elements(elements) => {
+                match elements.next() {
+                    Some(child) => { visit(child, visitor, &mut stack)?; }
+                    None => {
+                        stack.pop();
+                        visitor.leave_array()?;
+                    }
+                }
+            }
+            Children::Entries(entries) => {
+                match entries.next() {
+                    Some((key, child)) => {
+                        visitor.visit_key(key)?;
+                        visit(child, visitor, &mut stack)?;
+                    }
+                    None => {
+                        stack.pop();
+                        visitor.leave_object()?;
+                    }
+                }
+            }
+        }
+    }
+    Continue(())
+}
+
+// Visits a scalar item, or enters a container and stacks its children
+fn visit<'i, 'a, V: Visitor + ?Sized>(item: &'i JsonItem<'a>, visitor: &mut V, stack: &mut Vec<Children<'i, 'a>>) -> ControlFlow<()> {
+    match item.get_type() {
+        Empty => { Continue(()) }
+        JsonNull => { visitor.visit_null() }
+        JsonTrue => { visitor.visit_bool(true) }
+        JsonFalse => { visitor.visit_bool(false) }
+        JsonNumber => { visitor.visit_number(item.slice.as_str()) }
+        JsonString => { visitor.visit_string(&item.as_string().unwrap_or_default()) }
+        JsonArray => {
+            visitor.enter_array(item.len())?;
+            stack.extend(item.elements().map(Children::Elements));
+            Continue(())
+        }
+        JsonMap => {
+            visitor.enter_object(item.len())?;
+            stack.extend(item.entries().map(Children::Entries));
+            Continue(())
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants)]
+mod tests {
+    use std::ops::ControlFlow;
+    use std::ops::ControlFlow::{Break, Continue};
+
+    use crate::key::Key;
+    use crate::parse;
+    use crate::visitor::Visitor;
+
+    // Records events, stopping at the first object with more entries than the limit
+    struct Events {
+        events: Vec<String>,
+        max_entries: usize,
+    }
+
+    impl Visitor for Events {
+        fn visit_null(&mut self) -> ControlFlow<()> {
+            self.events.push("null".to_owned());
+            Continue(())
+        }
+
+        fn visit_bool(&mut self, value: bool) -> ControlFlow<()> {
+            self.events.push(value.to_string());
+            Continue(())
+        }
+
+        fn visit_number(&mut self, value: &str) -> ControlFlow<()> {
+            self.events.push(value.to_owned());
+            Continue(())
+        }
+
+        fn visit_string(&mut self, value: &str) -> ControlFlow<()> {
+            self.events.push(format!("'{}'", value));
+            Continue(())
+        }
+
+        fn enter_object(&mut self, len: usize) -> ControlFlow<()> {
+            if len > self.max_entries {
+                return Break(());
+            }
+            self.events.push(format!("{{{}", len));
+            Continue(())
+        }
+
+        fn visit_key(&mut self, key: &Key<'_>) -> ControlFlow<()> {
+            self.events.push(format!("{}:", key.decoded()));
+            Continue(())
+        }
+
+        fn leave_object(&mut self) -> ControlFlow<()> {
+            self.events.push("}".to_owned());
+            Continue(())
+        }
+
+        fn enter_array(&mut self, len: usize) -> ControlFlow<()> {
+            self.events.push(format!("[{}", len));
+            Continue(())
+        }
+
+        fn leave_array(&mut self) -> ControlFlow<()> {
+            self.events.push("]".to_owned());
+            Continue(())
+        }
+    }
+
+    #[test]
+    fn events() {
+        match parse("[{\"a\\u0062\": [null, true, -1.5e3]}, {}, \"\\u00e9\", false, {\"c\": 1, \"d\": 2}]") {
+            Ok(parsed) => {
+                let mut visitor = Events { events: Vec::new(), max_entries: 1 };
+                assert!(parsed.accept(&mut visitor).is_break());
+                assert_eq!(visitor.events.join(" "), "[5 {1 ab: [3 null true -1.5e3 ] } {0 } 'é' false");
+
+                let mut visitor = Events { events: Vec::new(), max_entries: 2 };
+                assert!(parsed.accept(&mut visitor).is_continue());
+                assert!(visitor.events.join(" ").ends_with("false {2 c: 1 d: 2 } ]"));
+
+                let mut visitor = Events { events: Vec::new(), max_entries: 0 };
+                assert!(parsed[2].accept(&mut visitor).is_continue() && parsed[9].accept(&mut visitor).is_continue());
+                assert_eq!(visitor.events, ["'é'"]);
+            }
+            Err(error) => {
+                assert!(false, "{}", error.to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn deep_nesting() {
+        struct Depth(usize, usize);
+
+        impl Visitor for Depth {
+            fn enter_object(&mut self, _: usize) -> ControlFlow<()> {
+                self.0 += 1;
+                self.1 = self.1.max(self.0);
+                Continue(())
+            }
+
+            fn leave_object(&mut self) -> ControlFlow<()> {
+                self.0 -= 1;
+                Continue(())
+            }
+        }
+
+        let depth = 500_000;
+        let json = "{\"a\":".repeat(depth) + "null" + &"}".repeat(depth);
+        match parse(&json) {
+            Ok(parsed) => {
+                let mut visitor = Depth(0, 0);
+                assert!(parsed.accept(&mut visitor).is_continue());
+                assert_eq!((visitor.0, visitor.1), (0, depth));
+            }
+            Err(error) => {
+                assert!(false, "{}", error.to_string());
+            }
+        }
+    }
+}
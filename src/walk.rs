@@ -0,0 +1,294 @@
+use std::iter::{Enumerate, FusedIterator};
+
+use crate::generics::{ArrayIterator, MapIterator};
+use crate::json_item::JsonItem;
+use crate::jsonpath::{NormalizedPath, PathSegment};
+use crate::key::Key;
+
+/// Order in which a [`Walker`] yields items
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum WalkOrder {
+    /// Items come before their children
+    #[default]
+    PreOrder,
+    /// Items come after their children
+    PostOrder,
+}
+
+// Path step to an item being walked, keys being decoded only when a path is built
+#[derive(Clone, Copy)]
+enum Step<'i, 'a> {
+    Index(usize),
+    Key(&'i Key<'a>),
+}
+
+// Children of an item being walked
+enum Children<'i, 'a> {
+    Elements(Enumerate<ArrayIterator<'i, JsonItem<'a>>>),
+    Entries(MapIterator<'i, Key<'a>, JsonItem<'a>>),
+}
+
+impl<'i, 'a> Iterator for Children<'i, 'a> {
+    type Item = (Step<'i, 'a>, &'i JsonItem<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Children::Elements(elements) => { elements.next().map(|(index, item)| (Step::Index(index), item)) }
+            Children::Entries(entries) => { entries.next().map(|(key, item)| (Step::Key(key), item)) }
+        }
+    }
+}
+
+// Item whose children are being walked
+struct Frame<'i, 'a> {
+    item: &'i JsonItem<'a>,
+    depth: usize,
+    children: Children<'i, 'a>,
+}
+
+/// Depth-first iterator over an item and its descendants in document order, yielding each item with its path
+/// and depth, the starting item being at depth 0. Nothing is yielded for items that do not exist. See [`JsonItem::walk`].
+///
+/// ```rust
+/// use jsonic::walk::WalkOrder;
+///
+/// let parsed = jsonic::parse("{\"a\": [1, {\"b\": null}], \"c\": {\"d\": true}}").unwrap();
+///
+/// let mut walker = parsed.walk();
+/// let mut paths = Vec::new();
+/// while let Some((path, item, _)) = walker.next() {
+///     if item.get("d").is_some() {
+///         walker.skip_subtree();
+///     }
+///     paths.push(path.to_dotted());
+/// }
+/// assert_eq!(paths, ["$", "$.a", "$.a[0]", "$.a[1]", "$.a[1].b", "$.c"]);
+///
+/// let paths: Vec<String> = parsed.walk().order(WalkOrder::PostOrder).max_depth(1).map(|(path, _, _)| path.to_pointer().to_string()).collect();
+/// assert_eq!(paths, ["/a", "/c", ""]);
+///
+/// let leaves: Vec<(String, usize)> = parsed.walk().leaves_only(true).map(|(path, _, depth)| (path.to_string(), depth)).collect();
+/// assert_eq!(leaves, [("$['a'][0]".to_owned(), 2), ("$['a'][1]['b']".to_owned(), 3), ("$['c']['d']".to_owned(), 2)]);
+/// ```
+pub struct Walker<'i, 'a> {
+    root: Option<&'i JsonItem<'a>>,
+    stack: Vec<Frame<'i, 'a>>,
+    path: Vec<Step<'i, 'a>>,
+    order: WalkOrder,
+    max_depth: usize,
+    leaves_only: bool,
+    // Whether the children of the last item yielded in pre-order are to be walked next
+    expanded: bool,
+}
+
+impl<'i, 'a> Walker<'i, 'a> {
+    pub(crate) fn new(root: &'i JsonItem<'a>) -> Self {
+        Walker {
+            root: Some(root).filter(|root| root.exists()),
+            stack: Vec::new(),
+            path: Vec::new(),
+            order: WalkOrder::PreOrder,
+            max_depth: usize::MAX,
+            leaves_only: false,
+            expanded: false,
+        }
+    }
+
+    /// Sets the order in which items are yielded. Default is [`WalkOrder::PreOrder`].
+    pub fn order(mut self, order: WalkOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Sets the maximum depth of yielded items, their children being skipped. Default is unlimited.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Sets whether only leaves are yielded, that is scalars and empty arrays or objects. Default is `false`.
+    pub fn leaves_only(mut self, leaves_only: bool) -> Self {
+        self.leaves_only = leaves_only;
+        self
+    }
+
+    /// Skips the children of the item last yielded. In post-order, children are yielded first, so this has no effect.
+    pub fn skip_subtree(&mut self) {
+        if self.expanded {
+            self.stack.pop();
+            self.expanded = false;
+        }
+    }
+
+    // Starts walking the children of item, if it has any and they are within the maximum depth
+    fn expand(&mut self, item: &'i JsonItem<'a>, depth: usize) -> bool {
+        if depth >= self.max_depth {
+            return false;
+        }
+        let children = match (item.elements(), item.entries()) {
+            (Some(elements), _) if elements.len() > 0 => { Children::Elements(elements.enumerate()) }
+            (_, Some(entries)) if entries.len() > 0 => { Children::Entries(entries) }
+            _ => { return false; }
+        };
+        self.stack.push(Frame { item, depth, children });
+        true
+    }
+
+    // Next item in pre-order, along with its depth
+    fn next_pre_order(&mut self) -> Option<(&'i JsonItem<'a>, usize)> {
+        let (item, depth) = match self.root.take() {
+            Some(root) => { (root, 0) }
+            None => {
+                loop {
+                    let frame = self.stack.last_mut()?;
+                    match frame.children.next() {
+                        Some((step, child)) => {
+                            let depth = frame.depth + 1;
+                            self.path.truncate(frame.depth);
+                            self.path.push(step);
+                            break (child, depth);
+                        }
+                        None => { self.stack.pop(); }
+                    }
+                }
+            }
+        };
+        self.expanded = self.expand(item, depth);
+        Some((item, depth))
+    }
+
+    // Next item in post-order, along with its depth
+    fn next_post_order(&mut self) -> Option<(&'i JsonItem<'a>, usize)> {
+        if let Some(root) = self.root.take() {
+            if !self.expand(root, 0) {
+                return Some((root, 0));
+            }
+        }
+        loop {
+            let frame = self.stack.last_mut()?;
+            match frame.children.next() {
+                Some((step, child)) => {
+                    let depth = frame.depth + 1;
+                    self.path.truncate(frame.depth);
+                    self.path.push(step);
+                    if !self.expand(child, depth) {
+                        return Some((child, depth));
+                    }
+                }
+                None => {
+                    let frame = self.stack.pop()?;
+                    self.path.truncate(frame.depth);
+                    return Some((frame.item, frame.depth));
+                }
+            }
+        }
+    }
+
+    // Next item to yield, along with its depth. Its path is only built on demand, see path.
+    pub(crate) fn next_item(&mut self) -> Option<(&'i JsonItem<'a>, usize)> {
+        loop {
+            let (item, depth) = match self.order {
+                WalkOrder::PreOrder => { self.next_pre_order()? }
+                WalkOrder::PostOrder => { self.next_post_order()? }
+            };
+            if !self.leaves_only || item.is_empty() {
+                return Some((item, depth));
+            }
+        }
+    }
+
+    // Path of the item last returned by next_item, decoding the keys along it
+    pub(crate) fn path(&self, depth: usize) -> NormalizedPath {
+        NormalizedPath::new(self.path[..depth].iter().map(|step| {
+            match step {
+                Step::Index(index) => { PathSegment::Index(*index) }
+                Step::Key(key) => { PathSegment::Key(key.decoded().into_owned()) }
+            }
+        }).collect())
+    }
+}
+
+impl<'i, 'a> Iterator for Walker<'i, 'a> {
+    type Item = (NormalizedPath, &'i JsonItem<'a>, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (item, depth) = self.next_item()?;
+        Some((self.path(depth), item, depth))
+    }
+}
+
+impl FusedIterator for Walker<'_, '_> {}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants)]
+mod tests {
+    use crate::parse;
+    use crate::walk::WalkOrder;
+
+    const JSON: &str = "{\"a\": [1, [], {\"b\": null}], \"c\": {}, \"d\": \"e\"}";
+
+    #[test]
+    fn orders() {
+        match parse(JSON) {
+            Ok(parsed) => {
+                let pre: Vec<(String, usize)> = parsed.walk().map(|(path, _, depth)| (path.to_pointer().to_string(), depth)).collect();
+                let mut post: Vec<(String, usize)> = parsed.walk().order(WalkOrder::PostOrder).map(|(path, _, depth)| (path.to_pointer().to_string(), depth)).collect();
+                assert_eq!(pre.len(), 8);
+                assert_eq!(pre[..4], [("".to_owned(), 0), ("/a".to_owned(), 1), ("/a/0".to_owned(), 2), ("/a/1".to_owned(), 2)]);
+                assert_eq!(post[..4], [("/a/0".to_owned(), 2), ("/a/1".to_owned(), 2), ("/a/2/b".to_owned(), 3), ("/a/2".to_owned(), 2)]);
+                assert_eq!(post.last(), Some(&("".to_owned(), 0)));
+                post.sort();
+                let mut sorted = pre.clone();
+                sorted.sort();
+                assert_eq!(post, sorted);
+                for (path, item, _) in parsed.walk() {
+                    assert!(path.to_pointer().evaluate(&parsed).is_some_and(|found| std::ptr::eq(found, item)));
+                }
+            }
+            Err(error) => {
+                assert!(false, "{}", error.to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn depth_skip_and_leaves() {
+        match parse(JSON) {
+            Ok(parsed) => {
+                assert_eq!(parsed.walk().max_depth(0).count(), 1);
+                assert_eq!(parsed.walk().max_depth(1).count(), 4);
+                assert_eq!(parsed.walk().order(WalkOrder::PostOrder).max_depth(1).count(), 4);
+
+                let mut walker = parsed.walk();
+                assert!(walker.next().is_some());
+                walker.skip_subtree();
+                assert!(walker.next().is_none());
+
+                let leaves: Vec<String> = parsed.walk().leaves_only(true).map(|(path, _, _)| path.to_dotted()).collect();
+                assert_eq!(leaves, ["$.a[0]", "$.a[1]", "$.a[2].b", "$.c", "$.d"]);
+                let leaves = parsed.walk().order(WalkOrder::PostOrder).leaves_only(true).map(|(path, _, _)| path.to_dotted());
+                assert!(leaves.eq(parsed.walk().leaves_only(true).map(|(path, _, _)| path.to_dotted())));
+                assert!(parsed["z"].walk().next().is_none());
+                assert_eq!(parsed["d"].walk().map(|(path, item, depth)| (path.to_string(), item.as_str(), depth)).collect::<Vec<_>>(), [("$".to_owned(), Some("e"), 0)]);
+            }
+            Err(error) => {
+                assert!(false, "{}", error.to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn deep_nesting() {
+        let depth = 100_000;
+        let json = "[".repeat(depth) + &"]".repeat(depth);
+        match parse(&json) {
+            Ok(parsed) => {
+                assert_eq!(parsed.walk().leaves_only(true).map(|(path, _, depth)| (path.segments().len(), depth)).collect::<Vec<_>>(), [(depth - 1, depth - 1)]);
+                assert_eq!(parsed.walk().order(WalkOrder::PostOrder).next().map(|(_, _, depth)| depth), Some(depth - 1));
+            }
+            Err(error) => {
+                assert!(false, "{}", error.to_string());
+            }
+        }
+    }
+}